@@ -0,0 +1,283 @@
+//! Command line client for pasty built on the `pasty-rs` API clients.
+//!
+//! This binary is only built with the `cli` feature enabled.
+
+use clap::{Args, Parser, Subcommand};
+use pasty_rs::{
+    client::{AuthenticatedClient, UnauthenticatedClient},
+    disk_cache::DiskCache,
+    model::{CreatedPaste, PasteUpdate},
+    token_store::FileTokenStore,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+/// Command line client for pasty.
+#[derive(Parser)]
+#[command(name = "pasty", version)]
+struct Cli {
+    /// URL of the pasty instance.
+    #[arg(
+        long,
+        global = true,
+        env = "PASTY_HOST",
+        default_value = "https://pasty.lus.pm"
+    )]
+    host: String,
+
+    /// Print output as JSON.
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a paste from each given file or a single paste from stdin.
+    Create {
+        /// Files to create pastes from.
+        files: Vec<PathBuf>,
+    },
+
+    /// Print a paste.
+    Get {
+        /// ID of the paste.
+        id: String,
+
+        /// Neither serve the paste from nor store it in the disk cache.
+        #[arg(long)]
+        no_cache: bool,
+    },
+
+    /// Update the content of a paste from the given file or stdin and/or
+    /// its metadata.
+    Update {
+        /// ID of the paste.
+        id: String,
+
+        /// File to read the new content from. When metadata is updated and
+        /// no file is given, the content is left unchanged.
+        file: Option<PathBuf>,
+
+        /// Set a metadata key. The value is parsed as JSON and used as
+        /// string if it is not valid JSON.
+        #[arg(long = "set", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        set: Vec<(String, serde_json::Value)>,
+
+        /// Remove a metadata key.
+        #[arg(long = "unset", value_name = "KEY")]
+        unset: Vec<String>,
+
+        #[command(flatten)]
+        auth: Auth,
+    },
+
+    /// Delete a paste.
+    Delete {
+        /// ID of the paste.
+        id: String,
+
+        #[command(flatten)]
+        auth: Auth,
+    },
+
+    /// Report a paste.
+    Report {
+        /// ID of the paste.
+        id: String,
+
+        /// Reason of the report.
+        reason: String,
+    },
+
+    /// Print information about the pasty instance.
+    Info,
+}
+
+#[derive(Args)]
+struct Auth {
+    /// Modification token of the paste. Defaults to the token recorded
+    /// when the paste was created with this tool.
+    #[arg(long, env = "PASTY_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let mut client = UnauthenticatedClient::new(&cli.host)?;
+    if let Some(path) = FileTokenStore::default_path() {
+        client = client.with_token_store(FileTokenStore::new(path));
+    }
+    let no_cache = matches!(cli.command, Command::Get { no_cache: true, .. });
+    if let Some(dir) = DiskCache::default_dir().filter(|_| !no_cache) {
+        client = client.with_disk_cache(DiskCache::new(dir));
+    }
+
+    match cli.command {
+        Command::Create { files } => {
+            if files.is_empty() {
+                let paste = client.create_paste(read_stdin()?, None).await?;
+                print_created(&client, &paste, None, cli.json);
+            }
+            for file in files {
+                let paste = client
+                    .create_paste(fs::read_to_string(&file)?, None)
+                    .await?;
+                print_created(&client, &paste, Some(&file), cli.json);
+            }
+        }
+        Command::Get { id, .. } => {
+            let paste = client.paste_with_fallback(&id).await?;
+            if paste.stale {
+                eprintln!("warning: pasty is unreachable, showing a cached version of paste {id}");
+            }
+            if cli.json {
+                print_json(&paste);
+            } else {
+                print!("{}", paste.paste.content);
+            }
+        }
+        Command::Update {
+            id,
+            file,
+            set,
+            unset,
+            auth,
+        } => {
+            let mut update = PasteUpdate::new();
+            for (key, value) in set {
+                update = update.set_metadata_key(key, &value)?;
+            }
+            for key in unset {
+                update = update.remove_metadata_key(key);
+            }
+            match file {
+                Some(file) => update = update.content(fs::read_to_string(file)?),
+                None if update.is_empty() => update = update.content(read_stdin()?),
+                None => {}
+            }
+
+            let client = authenticate(client, &id, auth)?;
+            client.update_paste_with(&id, &update).await?;
+            if cli.json {
+                print_json(&json!({ "id": id, "updated": true }));
+            } else {
+                println!("updated paste {id}");
+            }
+        }
+        Command::Delete { id, auth } => {
+            let client = authenticate(client, &id, auth)?;
+            client.delete_paste(&id).await?;
+            if cli.json {
+                print_json(&json!({ "id": id, "deleted": true }));
+            } else {
+                println!("deleted paste {id}");
+            }
+        }
+        Command::Report { id, reason } => {
+            let res = client.report_paste(&id, reason).await?;
+            if cli.json {
+                print_json(&res);
+            } else {
+                println!("{}", res.message);
+            }
+        }
+        Command::Info => {
+            let info = client.application_information().await?;
+            if cli.json {
+                print_json(&info);
+            } else {
+                println!("version:             {}", info.version);
+                println!("modification tokens: {}", info.modification_tokens);
+                println!("reports:             {}", info.reports);
+                println!("paste lifetime:      {}", info.paste_lifetime);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn authenticate(
+    client: UnauthenticatedClient,
+    id: &str,
+    auth: Auth,
+) -> pasty_rs::errors::Result<AuthenticatedClient> {
+    match auth.token {
+        Some(token) => Ok(client.authenticate(token)),
+        None => client.authenticate_paste(id),
+    }
+}
+
+fn parse_key_value(s: &str) -> Result<(String, serde_json::Value), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got `{s}`"))?;
+    let value = serde_json::from_str(value).unwrap_or_else(|_| value.into());
+    Ok((key.to_string(), value))
+}
+
+fn read_stdin() -> io::Result<String> {
+    let mut content = String::new();
+    io::stdin().read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// Prints a created paste. In JSON mode, one object is printed per line
+/// so that creating multiple pastes yields JSON lines.
+fn print_created(
+    client: &UnauthenticatedClient,
+    paste: &CreatedPaste,
+    file: Option<&PathBuf>,
+    json: bool,
+) {
+    let url = client.share_url(&paste.paste.id).to_string();
+
+    if json {
+        println!(
+            "{}",
+            json!({
+                "file": file,
+                "id": paste.paste.id,
+                "modificationToken": paste.modification_token.expose(),
+                "url": url,
+            })
+        );
+        return;
+    }
+
+    if let Some(file) = file {
+        println!("file:               {}", file.display());
+    }
+    println!("id:                 {}", paste.paste.id);
+    println!("modification token: {}", paste.modification_token.expose());
+    println!("url:                {url}");
+}
+
+fn print_json(value: &impl Serialize) {
+    match serde_json::to_string_pretty(value) {
+        Ok(s) => println!("{s}"),
+        Err(err) => eprintln!("error: {err}"),
+    }
+}
@@ -0,0 +1,194 @@
+use super::UnauthenticatedClient;
+#[cfg(feature = "reqwest")]
+use crate::transport::ReqwestTransport;
+use crate::{
+    errors::Result,
+    model::{AdminToken, Metadata, PasteUpdate},
+    transport::Transport,
+};
+use std::fmt;
+#[cfg(feature = "tracing")]
+use tracing::field::Empty;
+
+/// API client to perform privileged requests to the pasty API using an
+/// admin token.
+///
+/// In contrast to `AuthenticatedClient`, which is bound to the
+/// modification token of a single paste, this client may modify and
+/// delete any paste of the instance. Keeping both types apart makes sure
+/// that moderation flows can not be invoked with a per-paste token.
+///
+/// This client can be created from an `UnauthenticatedClient` instance.
+///
+/// # Example
+/// ```no_run
+/// # use pasty_rs::{client::*, model::AdminToken};
+/// # #[tokio::main]
+/// # async fn main() {
+/// let admin_client = UnauthenticatedClient::new("https://pasty.lus.pm")
+///     .unwrap()
+///     .admin(AdminToken::new("some-admin-token"));
+///
+/// for (id, res) in admin_client.delete_pastes(["abcdef", "ghijkl"]).await {
+///     if let Err(err) = res {
+///         eprintln!("failed deleting paste {id}: {err}");
+///     }
+/// }
+/// # }
+/// ```
+///
+/// # Reference
+/// Implementation according to the pasty API documentation:
+/// https://github.com/lus/pasty/blob/master/API.md#api
+#[derive(Clone)]
+pub struct AdminClient<
+    #[cfg(feature = "reqwest")] T = ReqwestTransport,
+    #[cfg(not(feature = "reqwest"))] T,
+> {
+    client: UnauthenticatedClient<T>,
+    token: AdminToken,
+}
+
+impl<T> fmt::Debug for AdminClient<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AdminClient")
+            .field("host", &self.client.state.host.as_str())
+            .field("token", &self.token)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Transport> UnauthenticatedClient<T> {
+    /// Consumes the `UnauthenticatedClient` and a given admin token to
+    /// perform instance-wide privileged requests.
+    ///
+    /// Only an `AdminToken` is accepted, so that the modification token
+    /// of a paste can not be used by accident:
+    /// ```compile_fail
+    /// # use pasty_rs::client::*;
+    /// # async fn f(client: UnauthenticatedClient) {
+    /// let created = client.create_paste("hello pasty!", None).await.unwrap();
+    /// let admin_client = client.admin(created.modification_token);
+    /// # }
+    /// ```
+    pub fn admin(self, token: AdminToken) -> AdminClient<T> {
+        AdminClient {
+            client: self,
+            token,
+        }
+    }
+}
+
+impl<T: Transport> AdminClient<T> {
+    /// Returns a reference to the inner `UnauthenticatedClient` instance.
+    pub fn inner(&self) -> &UnauthenticatedClient<T> {
+        &self.client
+    }
+
+    /// Updates a given content and metadata of any paste by it's ID.
+    ///
+    /// # Reference
+    /// Binds to the `PATCH /api/v2/pastes/{paste_id}` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#paste_specific-update-a-paste
+    pub async fn update_paste(
+        &self,
+        id: &str,
+        content: impl Into<String>,
+        metadata: Option<Metadata>,
+    ) -> Result<()> {
+        let mut update = PasteUpdate::new().content(content);
+        if let Some(metadata) = metadata {
+            update = update.metadata(&metadata)?;
+        }
+        self.update_paste_with(id, &update).await
+    }
+
+    /// Partially updates any paste by it's ID with the given `PasteUpdate`.
+    ///
+    /// # Reference
+    /// Binds to the `PATCH /api/v2/pastes/{paste_id}` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#paste_specific-update-a-paste
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            err,
+            fields(
+                endpoint = "PATCH /api/v2/pastes/{paste_id}",
+                paste_id = id,
+                status = Empty,
+                latency_ms = Empty,
+                response_size = Empty,
+                retries = Empty,
+            )
+        )
+    )]
+    pub async fn update_paste_with(&self, id: &str, update: &PasteUpdate) -> Result<()> {
+        self.client
+            .patch_paste(self.token.expose(), id, update)
+            .await
+    }
+
+    /// Deletes any paste by it's ID.
+    ///
+    /// # Reference
+    /// Binds to the `DELETE /api/v2/pastes/{paste_id}` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#paste_specific-delete-a-paste
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            err,
+            fields(
+                endpoint = "DELETE /api/v2/pastes/{paste_id}",
+                paste_id = id,
+                status = Empty,
+                latency_ms = Empty,
+                response_size = Empty,
+                retries = Empty,
+            )
+        )
+    )]
+    pub async fn delete_paste(&self, id: &str) -> Result<()> {
+        self.client.remove_paste(self.token.expose(), id).await
+    }
+
+    /// Deletes all given pastes by their IDs.
+    ///
+    /// The pastes are deleted one after another. A failing deletion does
+    /// not abort the remaining ones. The result of each deletion is
+    /// returned alongside the respective paste ID.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "DELETE /api/v2/pastes/{paste_id}",
+                pastes = Empty,
+                failed = Empty,
+            )
+        )
+    )]
+    pub async fn delete_pastes<I, S>(&self, ids: I) -> Vec<(String, Result<()>)>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut results = Vec::new();
+        for id in ids {
+            let id = id.into();
+            let res = self.delete_paste(&id).await;
+            results.push((id, res));
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::Span::current()
+            .record("pastes", results.len())
+            .record(
+                "failed",
+                results.iter().filter(|(_, res)| res.is_err()).count(),
+            );
+
+        results
+    }
+}
@@ -0,0 +1,643 @@
+//! Blocking counterparts of the async API clients.
+//!
+//! This module is only available with the `blocking` feature enabled.
+//! The clients in here expose the same endpoints as the clients in the
+//! parent module, but perform their requests synchronously so that no
+//! async runtime is required.
+
+#[cfg(feature = "tracing")]
+use super::record_response;
+use super::{
+    api_url, check_status,
+    state::{ensure_supported, ClientState},
+    IntoUrl, PasteCache, RetryPolicy,
+};
+#[cfg(feature = "reqwest")]
+use crate::transport::ReqwestBlockingTransport;
+use crate::{
+    disk_cache::DiskCache,
+    errors::Result,
+    model::{
+        ApplicationInformation, CachedPaste, CreatePasteRequest, CreatedPaste, Metadata,
+        ModificationToken, Paste, PasteUpdate, ReportRequest, ReportResponse,
+    },
+    share::ShareUrl,
+    token_store::TokenStore,
+    transport::{BlockingTransport, HttpRequest, HttpResponse, Method},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fmt, sync::Arc};
+#[cfg(feature = "tracing")]
+use tracing::field::Empty;
+use url::Url;
+
+mod admin;
+pub use admin::AdminClient;
+
+/// Blocking API client to perform unauthenticated requests to the
+/// pasty API.
+///
+/// The client is generic over the `BlockingTransport` used to perform
+/// HTTP requests, which defaults to `ReqwestBlockingTransport` if the
+/// `reqwest` feature is enabled.
+///
+/// # Reference
+/// Implementation according to the pasty API documentation:
+/// https://github.com/lus/pasty/blob/master/API.md#api
+#[derive(Clone)]
+pub struct UnauthenticatedClient<
+    #[cfg(feature = "reqwest")] T = ReqwestBlockingTransport,
+    #[cfg(not(feature = "reqwest"))] T,
+> {
+    transport: T,
+    state: ClientState,
+}
+
+#[cfg(feature = "reqwest")]
+impl UnauthenticatedClient {
+    /// Creates a new instance of UnauthenticatedClient with the given
+    /// host URL.
+    ///
+    /// If the host URL contains a path, for example when pasty is served
+    /// under a sub-path behind a reverse proxy, the path is preserved as
+    /// base path for all requests.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use pasty_rs::client::blocking::*;
+    /// let client = UnauthenticatedClient::new("https://pasty.lus.pm").unwrap();
+    /// let res = client.application_information().unwrap();
+    /// ```
+    ///
+    /// # Reference
+    /// Implementation according to the pasty API documentation:
+    /// https://github.com/lus/pasty/blob/master/API.md#api
+    pub fn new(host: impl IntoUrl) -> Result<Self> {
+        Self::with_transport(host, ReqwestBlockingTransport::default())
+    }
+}
+
+impl<T: BlockingTransport> UnauthenticatedClient<T> {
+    /// Creates a new instance of UnauthenticatedClient with the given
+    /// host URL, which performs its requests with the given transport.
+    pub fn with_transport(host: impl IntoUrl, transport: T) -> Result<Self> {
+        Ok(Self {
+            transport,
+            state: ClientState::new(host.into_url()?),
+        })
+    }
+
+    /// Returns a reference to the transport used to perform requests.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Sets the policy used to retry failed requests.
+    ///
+    /// By default, failed requests are not retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.state.retry_policy = retry_policy;
+        self
+    }
+
+    /// Enables checking the `capabilities` of the pasty instance before
+    /// requests using modification tokens, so that they fail early with
+    /// `Error::Unsupported` if modification tokens are disabled.
+    ///
+    /// The capabilities are requested on the first such request and are
+    /// cached afterwards. If they can not be requested, the operation
+    /// fails as well. By default, such requests are rejected by the pasty
+    /// instance itself. Reports are always checked via `report_paste`.
+    pub fn with_capability_checks(mut self) -> Self {
+        self.state.capability_checks = true;
+        self
+    }
+
+    /// Sets a `TokenStore` in which the modification tokens of all pastes
+    /// created with this client are recorded.
+    ///
+    /// Stored tokens can be used to authenticate for a paste via
+    /// `authenticate_paste` and are removed when the paste is deleted
+    /// via `AuthenticatedClient::delete_paste`. Failing to access the
+    /// store is logged and does not fail the creation or deletion, so
+    /// that the token of a created paste is always returned.
+    pub fn with_token_store(mut self, token_store: impl TokenStore + 'static) -> Self {
+        self.state.token_store = Some(Arc::new(token_store));
+        self
+    }
+
+    /// Sets a `PasteCache` in which pastes requested via `paste` are
+    /// cached.
+    ///
+    /// The cache is shared with all clones of this client, including
+    /// authenticated clients created from it, so that updating or
+    /// deleting a paste through them invalidates the cached paste.
+    ///
+    /// Pastes are cached at most until they expire on the pasty instance,
+    /// for which its `capabilities` are requested once when caching the
+    /// first paste.
+    pub fn with_cache(mut self, cache: PasteCache) -> Self {
+        self.state.cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Returns the `PasteCache` used by this client, if any.
+    pub fn cache(&self) -> Option<&PasteCache> {
+        self.state.cache.as_deref()
+    }
+
+    /// Sets a `DiskCache` in which pastes requested via `paste` are
+    /// persisted, so that `paste_with_fallback` can serve them while the
+    /// pasty instance is unreachable.
+    ///
+    /// Like the `PasteCache`, the cached pastes are invalidated when
+    /// updating or deleting them through this client or its clones.
+    pub fn with_disk_cache(mut self, disk_cache: DiskCache) -> Self {
+        self.state.disk_cache = Some(Arc::new(disk_cache));
+        self
+    }
+
+    /// Returns the `DiskCache` used by this client, if any.
+    pub fn disk_cache(&self) -> Option<&DiskCache> {
+        self.state.disk_cache.as_deref()
+    }
+
+    /// Returns the host URL of the pasty instance.
+    pub fn host(&self) -> &Url {
+        &self.state.host
+    }
+
+    /// Returns a `ShareUrl` linking to the paste with the given ID in
+    /// the pasty web frontend of this instance.
+    pub fn share_url(&self, id: &str) -> ShareUrl {
+        ShareUrl::new(self.state.host.clone(), id)
+    }
+
+    /// Returns generall application information of the pasty instance.
+    ///
+    /// # Reference
+    /// Binds to the `GET /api/v2/info` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#unsecured-retrieve-application-information
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            err,
+            fields(
+                endpoint = "GET /api/v2/info",
+                paste_id = Empty,
+                status = Empty,
+                latency_ms = Empty,
+                response_size = Empty,
+                retries = Empty,
+            )
+        )
+    )]
+    pub fn application_information(&self) -> Result<ApplicationInformation> {
+        let r = HttpRequest::new(Method::GET, api_url(&self.state.host, &["info"])?);
+        req_body(self, r)
+    }
+
+    /// Returns the application information of the pasty instance, which
+    /// describes the capabilities supported by it.
+    ///
+    /// The information is requested once and cached afterwards, also
+    /// across clones of this client. Operations which are not supported
+    /// by the instance fail early with `Error::Unsupported` based on it,
+    /// see `report_paste` and `with_capability_checks`.
+    ///
+    /// # Reference
+    /// Binds to the `GET /api/v2/info` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#unsecured-retrieve-application-information
+    pub fn capabilities(&self) -> Result<&ApplicationInformation> {
+        if let Some(info) = self.state.capabilities.get() {
+            return Ok(info);
+        }
+        let info = self.application_information()?;
+        Ok(self.state.capabilities.get_or_init(|| info))
+    }
+
+    /// Returns a pastes content by it's ID.
+    ///
+    /// If a `PasteCache` is set, the paste is served from the cache if
+    /// present and cached after it has been requested otherwise.
+    /// If a `DiskCache` is set, requested pastes are persisted in it.
+    ///
+    /// # Reference
+    /// Binds to the `GET /api/v2/pastes/{paste_id}` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#unsecured-retrieve-a-paste
+    pub fn paste(&self, id: &str) -> Result<Paste> {
+        if let Some(paste) = self.state.cached(id) {
+            return Ok(paste);
+        }
+
+        let paste = self.paste_as(id).inspect_err(|err| {
+            self.state.request_failed(id, err);
+        })?;
+
+        // The capabilities are cached after the first request, failing to
+        // request them only loses the cap on the ttl.
+        let info = if self.state.caches_pastes() {
+            self.capabilities().ok()
+        } else {
+            None
+        };
+        self.state.cache_paste(&paste, info);
+
+        Ok(paste)
+    }
+
+    /// Returns a pastes content by it's ID like `paste`, but falls back to
+    /// the `DiskCache` if the pasty instance is unreachable.
+    ///
+    /// Pastes served from the disk cache are flagged as `stale`. If no
+    /// disk cache is set or the paste has not been cached, the original
+    /// error is returned.
+    ///
+    /// # Reference
+    /// Binds to the `GET /api/v2/pastes/{paste_id}` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#unsecured-retrieve-a-paste
+    pub fn paste_with_fallback(&self, id: &str) -> Result<CachedPaste> {
+        self.state.with_fallback(id, self.paste(id))
+    }
+
+    /// Returns a pastes content by it's ID with its metadata deserialized
+    /// into the given type `M`.
+    ///
+    /// # Reference
+    /// Binds to the `GET /api/v2/pastes/{paste_id}` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#unsecured-retrieve-a-paste
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            err,
+            fields(
+                endpoint = "GET /api/v2/pastes/{paste_id}",
+                paste_id = id,
+                status = Empty,
+                latency_ms = Empty,
+                response_size = Empty,
+                retries = Empty,
+            )
+        )
+    )]
+    pub fn paste_as<M: DeserializeOwned>(&self, id: &str) -> Result<Paste<M>> {
+        let r = HttpRequest::new(Method::GET, api_url(&self.state.host, &["pastes", id])?);
+        req_body(self, r)
+    }
+
+    /// Creates a paste with the given content and metadata.
+    ///
+    /// # Reference
+    /// Binds to the `POST /api/v2/pastes` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#unsecured-create-a-paste
+    pub fn create_paste(
+        &self,
+        content: impl Into<String>,
+        metadata: Option<Metadata>,
+    ) -> Result<CreatedPaste> {
+        self.create_paste_as(content, metadata)
+    }
+
+    /// Creates a paste with the given content and metadata of the user
+    /// defined type `M`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use pasty_rs::client::blocking::*;
+    /// # use serde::{Deserialize, Serialize};
+    /// #[derive(Serialize, Deserialize)]
+    /// struct BuildInfo {
+    ///     build_id: u64,
+    ///     commit: String,
+    /// }
+    ///
+    /// let client = UnauthenticatedClient::new("https://pasty.lus.pm").unwrap();
+    ///
+    /// let info = BuildInfo {
+    ///     build_id: 1234,
+    ///     commit: "a1b2c3d".into(),
+    /// };
+    /// let created = client.create_paste_as("build log", Some(info)).unwrap();
+    ///
+    /// let paste = client.paste_as::<BuildInfo>(&created.paste.id).unwrap();
+    /// assert_eq!(paste.metadata.unwrap().build_id, 1234);
+    /// ```
+    ///
+    /// # Reference
+    /// Binds to the `POST /api/v2/pastes` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#unsecured-create-a-paste
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            err,
+            fields(
+                endpoint = "POST /api/v2/pastes",
+                paste_id = Empty,
+                status = Empty,
+                latency_ms = Empty,
+                response_size = Empty,
+                retries = Empty,
+            )
+        )
+    )]
+    pub fn create_paste_as<M: Serialize + DeserializeOwned>(
+        &self,
+        content: impl Into<String>,
+        metadata: Option<M>,
+    ) -> Result<CreatedPaste<M>> {
+        let r = HttpRequest::new(Method::POST, api_url(&self.state.host, &["pastes"])?).json(
+            &CreatePasteRequest {
+                content: content.into(),
+                metadata,
+            },
+        )?;
+        let paste: CreatedPaste<M> = req_body(self, r)?;
+
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("paste_id", paste.paste.id.as_str());
+
+        self.state
+            .store_token(&paste.paste.id, &paste.modification_token);
+
+        Ok(paste)
+    }
+
+    /// Reports a paste by it's ID with the given reason.
+    ///
+    /// If reports are disabled on the instance according to its
+    /// `capabilities`, `Error::Unsupported` is returned and no report is
+    /// sent.
+    ///
+    /// # Reference
+    /// Binds to the `POST /api/v2/pastes/{paste_id}/report` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#unsecured-report-a-paste
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            err,
+            fields(
+                endpoint = "POST /api/v2/pastes/{paste_id}/report",
+                paste_id = id,
+                status = Empty,
+                latency_ms = Empty,
+                response_size = Empty,
+                retries = Empty,
+            )
+        )
+    )]
+    pub fn report_paste(&self, id: &str, reason: impl Into<String>) -> Result<ReportResponse> {
+        ensure_supported("reports", self.capabilities()?.reports)?;
+
+        let r = HttpRequest::new(
+            Method::POST,
+            api_url(&self.state.host, &["pastes", id, "report"])?,
+        )
+        .json(&ReportRequest {
+            reason: reason.into(),
+        })?;
+        req_body(self, r)
+    }
+
+    /// Creates an `AuthenticatedClient` for the paste with the given ID
+    /// using the modification token recorded in the token store of this
+    /// client.
+    ///
+    /// Returns `Error::MissingToken` if no token store is set or no token
+    /// is stored for the paste.
+    pub fn authenticate_paste(&self, id: &str) -> Result<AuthenticatedClient<T>>
+    where
+        T: Clone,
+    {
+        let token = self.state.stored_token(id)?;
+        Ok(self.clone().authenticate(token))
+    }
+
+    /// Consumes the `UnauthenticatedClient` and a given paste modification
+    /// token to perform authenticated requests for that paste.
+    ///
+    /// To moderate pastes with an admin token, use `admin` instead.
+    pub fn authenticate(self, token: impl Into<ModificationToken>) -> AuthenticatedClient<T> {
+        AuthenticatedClient {
+            client: self,
+            token: token.into(),
+        }
+    }
+
+    fn patch_paste(&self, token: &str, id: &str, update: &PasteUpdate) -> Result<()> {
+        let r = HttpRequest::new(Method::PATCH, api_url(&self.state.host, &["pastes", id])?)
+            .json(update)?
+            .bearer_auth(token)?;
+        let res = req(self, r);
+        self.state.invalidate_cached(id);
+        res
+    }
+
+    fn remove_paste(&self, token: &str, id: &str) -> Result<()> {
+        let r = HttpRequest::new(Method::DELETE, api_url(&self.state.host, &["pastes", id])?)
+            .bearer_auth(token)?;
+        let res = req(self, r);
+        self.state.invalidate_cached(id);
+        res?;
+
+        self.state.remove_token(id);
+        Ok(())
+    }
+
+    /// Returns `Error::Unsupported` for the given feature if capability
+    /// checks are enabled and the feature is not supported according to
+    /// the capabilities of the pasty instance.
+    fn check_capability(
+        &self,
+        feature: &'static str,
+        supported: impl FnOnce(&ApplicationInformation) -> bool,
+    ) -> Result<()> {
+        if !self.state.capability_checks {
+            return Ok(());
+        }
+        ensure_supported(feature, supported(self.capabilities()?))
+    }
+}
+
+/// Blocking API client to perform authenticated requests to the
+/// pasty API.
+///
+/// This client can be created from an `UnauthenticatedClient` instance.
+///
+/// # Example
+/// ```
+/// # use pasty_rs::client::blocking::*;
+/// let client = UnauthenticatedClient::new("https://pasty.lus.pm").unwrap();
+/// let auth_client = client.authenticate("some-token");
+/// assert!(!format!("{auth_client:?}").contains("some-token"));
+/// ```
+///
+/// # Reference
+/// Implementation according to the pasty API documentation:
+/// https://github.com/lus/pasty/blob/master/API.md#api
+#[derive(Clone)]
+pub struct AuthenticatedClient<
+    #[cfg(feature = "reqwest")] T = ReqwestBlockingTransport,
+    #[cfg(not(feature = "reqwest"))] T,
+> {
+    client: UnauthenticatedClient<T>,
+    token: ModificationToken,
+}
+
+impl<T> fmt::Debug for AuthenticatedClient<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthenticatedClient")
+            .field("host", &self.client.state.host.as_str())
+            .field("token", &self.token)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: BlockingTransport> AuthenticatedClient<T> {
+    /// Returns a reference to the inner `UnauthenticatedClient` instance.
+    pub fn inner(&self) -> &UnauthenticatedClient<T> {
+        &self.client
+    }
+
+    /// Returns the token used to authenticate requests.
+    pub fn token(&self) -> &ModificationToken {
+        &self.token
+    }
+
+    /// Updates a given content and metadata by it's ID.
+    ///
+    /// The given metadata keys are merged into the existing metadata of
+    /// the paste. Use `update_paste_with` to only update the metadata or
+    /// to remove metadata keys.
+    ///
+    /// # Reference
+    /// Binds to the `PATCH /api/v2/pastes/{paste_id}` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#paste_specific-update-a-paste
+    pub fn update_paste(
+        &self,
+        id: &str,
+        content: impl Into<String>,
+        metadata: Option<Metadata>,
+    ) -> Result<()> {
+        let mut update = PasteUpdate::new().content(content);
+        if let Some(metadata) = metadata {
+            update = update.metadata(&metadata)?;
+        }
+        self.update_paste_with(id, &update)
+    }
+
+    /// Partially updates a paste by it's ID with the given `PasteUpdate`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use pasty_rs::{client::blocking::*, model::PasteUpdate};
+    /// # fn main() {
+    /// let client = UnauthenticatedClient::new("https://pasty.lus.pm")
+    ///     .unwrap()
+    ///     .authenticate("some-token");
+    ///
+    /// // Tag the paste without re-uploading its content.
+    /// let update = PasteUpdate::new()
+    ///     .set_metadata_key("status", &"reviewed")
+    ///     .unwrap()
+    ///     .remove_metadata_key("draft");
+    /// client.update_paste_with("abcdef", &update).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Reference
+    /// Binds to the `PATCH /api/v2/pastes/{paste_id}` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#paste_specific-update-a-paste
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            err,
+            fields(
+                endpoint = "PATCH /api/v2/pastes/{paste_id}",
+                paste_id = id,
+                status = Empty,
+                latency_ms = Empty,
+                response_size = Empty,
+                retries = Empty,
+            )
+        )
+    )]
+    pub fn update_paste_with(&self, id: &str, update: &PasteUpdate) -> Result<()> {
+        self.client
+            .check_capability("modification tokens", |info| info.modification_tokens)?;
+        self.client.patch_paste(self.token.expose(), id, update)
+    }
+
+    /// Deletes a paste by it's ID.
+    ///
+    /// # Reference
+    /// Binds to the `DELETE /api/v2/pastes/{paste_id}` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#paste_specific-delete-a-paste
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            err,
+            fields(
+                endpoint = "DELETE /api/v2/pastes/{paste_id}",
+                paste_id = id,
+                status = Empty,
+                latency_ms = Empty,
+                response_size = Empty,
+                retries = Empty,
+            )
+        )
+    )]
+    pub fn delete_paste(&self, id: &str) -> Result<()> {
+        self.client
+            .check_capability("modification tokens", |info| info.modification_tokens)?;
+        self.client.remove_paste(self.token.expose(), id)
+    }
+}
+
+fn req_body<T: DeserializeOwned>(
+    client: &UnauthenticatedClient<impl BlockingTransport>,
+    req: HttpRequest,
+) -> Result<T> {
+    let res = execute(client, req)?;
+    Ok(serde_json::from_slice(&res.body)?)
+}
+
+fn req(client: &UnauthenticatedClient<impl BlockingTransport>, req: HttpRequest) -> Result<()> {
+    execute(client, req)?;
+    Ok(())
+}
+
+/// Executes the given request, retrying it according to the retry policy
+/// of the client, and checks the status of the final response.
+fn execute(
+    client: &UnauthenticatedClient<impl BlockingTransport>,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    #[cfg(feature = "tracing")]
+    let started = std::time::Instant::now();
+    let policy = &client.state.retry_policy;
+    let mut retries = 0;
+
+    let res = loop {
+        if !policy.allows_retry(&req.method, retries) {
+            break client.transport.execute(req);
+        }
+
+        let res = client.transport.execute(req.clone());
+        match policy.delay(&res, retries) {
+            Some(delay) => std::thread::sleep(delay),
+            None => break res,
+        }
+        retries += 1;
+    };
+
+    #[cfg(feature = "tracing")]
+    record_response(started, retries, &res);
+
+    check_status(res?)
+}
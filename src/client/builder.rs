@@ -0,0 +1,162 @@
+use super::{IntoUrl, RetryPolicy, UnauthenticatedClient};
+use crate::{errors::Result, transport::ReqwestTransport};
+use reqwest::{header::HeaderMap, Client, Proxy};
+use std::time::Duration;
+
+/// Builder to create an `UnauthenticatedClient` with a custom HTTP
+/// client configuration.
+///
+/// This type is only available with the `reqwest` feature enabled.
+///
+/// # Example
+/// ```
+/// # use pasty_rs::client::*;
+/// # use std::time::Duration;
+/// let client = UnauthenticatedClient::builder()
+///     .timeout(Duration::from_secs(10))
+///     .user_agent("my-app/1.0")
+///     .build("https://pasty.lus.pm")
+///     .unwrap();
+/// ```
+#[derive(Default)]
+pub struct ClientBuilder {
+    client: Option<Client>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    default_headers: HeaderMap,
+    proxies: Vec<Proxy>,
+    retry_policy: RetryPolicy,
+    #[cfg(feature = "blocking")]
+    blocking_client: Option<reqwest::blocking::Client>,
+}
+
+impl ClientBuilder {
+    /// Creates a new `ClientBuilder` with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses the given, already configured `reqwest::Client` to perform
+    /// requests, for example to share a connection pool.
+    ///
+    /// When set, all other HTTP options of this builder are ignored by
+    /// `build`.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sets a timeout for the whole request, from connecting until the
+    /// response body has been read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets a timeout for only the connect phase of a request.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets headers which are sent with every request.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    /// Adds a proxy to route requests through.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Sets the policy used to retry failed requests.
+    ///
+    /// By default, failed requests are not retried.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Creates the `UnauthenticatedClient` for the given host URL.
+    pub fn build(self, host: impl IntoUrl) -> Result<UnauthenticatedClient> {
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let mut builder = Client::builder().default_headers(self.default_headers);
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                for proxy in self.proxies {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(
+            UnauthenticatedClient::with_transport(host, ReqwestTransport::new(client))?
+                .with_retry_policy(self.retry_policy),
+        )
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl ClientBuilder {
+    /// Uses the given, already configured `reqwest::blocking::Client` to
+    /// perform requests with the client created by `build_blocking`.
+    ///
+    /// When set, all other HTTP options of this builder are ignored by
+    /// `build_blocking`.
+    pub fn blocking_client(mut self, client: reqwest::blocking::Client) -> Self {
+        self.blocking_client = Some(client);
+        self
+    }
+
+    /// Creates a blocking `UnauthenticatedClient` for the given host URL.
+    pub fn build_blocking(
+        self,
+        host: impl IntoUrl,
+    ) -> Result<super::blocking::UnauthenticatedClient> {
+        let client = match self.blocking_client {
+            Some(client) => client,
+            None => {
+                let mut builder =
+                    reqwest::blocking::Client::builder().default_headers(self.default_headers);
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                for proxy in self.proxies {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(super::blocking::UnauthenticatedClient::with_transport(
+            host,
+            crate::transport::ReqwestBlockingTransport::new(client),
+        )?
+        .with_retry_policy(self.retry_policy))
+    }
+}
@@ -0,0 +1,159 @@
+use crate::model::Paste;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// An in-memory cache for pastes requested via `paste`.
+///
+/// The cache holds at most `capacity` pastes and evicts the least recently
+/// used paste when full. Each paste is cached for `ttl`, but never beyond
+/// the point in time at which the pasty instance deletes it according to
+/// its paste lifetime. The `capabilities` of the instance are requested
+/// for this on the first insert; if that fails, only `ttl` applies.
+///
+/// Updating or deleting a paste through a client using the cache, or any
+/// of its clones, invalidates the cached paste.
+///
+/// # Example
+/// ```
+/// # use pasty_rs::client::*;
+/// # use std::time::Duration;
+/// let client = UnauthenticatedClient::new("https://pasty.lus.pm")
+///     .unwrap()
+///     .with_cache(PasteCache::new(100, Duration::from_secs(60)));
+/// ```
+#[derive(Debug)]
+pub struct PasteCache {
+    capacity: usize,
+    ttl: Duration,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    entries: HashMap<String, Entry>,
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    paste: Paste,
+    /// `None` if the ttl exceeds the range of `Instant`.
+    expires: Option<Instant>,
+    last_used: u64,
+}
+
+impl PasteCache {
+    /// Creates a new cache holding up to `capacity` pastes, each for at
+    /// most the given `ttl`.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Returns the maximum number of cached pastes.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the maximum duration for which a paste is cached.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Returns the number of currently cached pastes, including expired
+    /// ones which have not been evicted yet.
+    pub fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    /// Returns `true` if no pastes are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes the paste with the given ID from the cache.
+    pub fn invalidate(&self, id: &str) {
+        self.state().entries.remove(id);
+    }
+
+    /// Removes all pastes from the cache.
+    pub fn clear(&self) {
+        self.state().entries.clear();
+    }
+
+    /// Returns the cached paste with the given ID, if it is cached and
+    /// has not expired yet.
+    pub(crate) fn get(&self, id: &str) -> Option<Paste> {
+        let mut state = self.state();
+        state.tick += 1;
+        let tick = state.tick;
+
+        let entry = state.entries.get_mut(id)?;
+        if entry.is_expired(Instant::now()) {
+            state.entries.remove(id);
+            return None;
+        }
+
+        entry.last_used = tick;
+        Some(entry.paste.clone())
+    }
+
+    /// Caches the given paste, which is deleted by the pasty instance at
+    /// `expires_at`, if known.
+    pub(crate) fn insert(&self, paste: Paste, expires_at: Option<SystemTime>) {
+        let ttl = match expires_at {
+            Some(expires_at) => match expires_at.duration_since(SystemTime::now()) {
+                Ok(remaining) => remaining.min(self.ttl),
+                Err(_) => return,
+            },
+            None => self.ttl,
+        };
+        if self.capacity == 0 || ttl.is_zero() {
+            return;
+        }
+
+        let mut state = self.state();
+        state.tick += 1;
+        let tick = state.tick;
+
+        if !state.entries.contains_key(&paste.id) && state.entries.len() >= self.capacity {
+            let now = Instant::now();
+            state.entries.retain(|_, entry| !entry.is_expired(now));
+        }
+        if !state.entries.contains_key(&paste.id) && state.entries.len() >= self.capacity {
+            let lru = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(id, _)| id.clone());
+            if let Some(lru) = lru {
+                state.entries.remove(&lru);
+            }
+        }
+
+        state.entries.insert(
+            paste.id.clone(),
+            Entry {
+                paste,
+                expires: Instant::now().checked_add(ttl),
+                last_used: tick,
+            },
+        );
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+}
@@ -0,0 +1,156 @@
+use super::{PasteHandle, UnauthenticatedClient};
+#[cfg(feature = "reqwest")]
+use crate::transport::ReqwestTransport;
+use crate::{errors::Result, model::Metadata, transport::Transport};
+use std::ops::Deref;
+
+/// A guard around a `PasteHandle` which deletes the paste when dropped.
+///
+/// This type is only available with the `tokio` feature enabled, which
+/// is implied by the default `reqwest` feature.
+///
+/// When dropped, the deletion is spawned as a task on the current tokio
+/// runtime. Such a task does not finish if the runtime shuts down first,
+/// which is the case at the end of every `#[tokio::test]`, so `close`
+/// must be called explicitly wherever the deletion has to be awaited.
+/// Deletions which fail, which are aborted or which can not be spawned
+/// at all are logged and never panic.
+///
+/// Use `keep` to disarm the guard and keep the paste.
+///
+/// # Example
+/// ```no_run
+/// # use pasty_rs::client::*;
+/// # #[tokio::main]
+/// # async fn main() {
+/// let client = UnauthenticatedClient::new("https://pasty.lus.pm").unwrap();
+///
+/// let paste = client
+///     .create_ephemeral_paste("diagnostics", None)
+///     .await
+///     .unwrap();
+/// println!("diagnostics available at {}", paste.share_url());
+///
+/// // Deletes the paste.
+/// paste.close().await.unwrap();
+/// # }
+/// ```
+pub struct EphemeralPaste<
+    #[cfg(feature = "reqwest")] T: Transport + 'static = ReqwestTransport,
+    #[cfg(not(feature = "reqwest"))] T: Transport + 'static,
+> {
+    handle: Option<PasteHandle<T>>,
+}
+
+impl<T: Transport + 'static> EphemeralPaste<T> {
+    /// Creates a new guard deleting the paste of the given handle when
+    /// dropped.
+    pub fn new(handle: PasteHandle<T>) -> Self {
+        Self {
+            handle: Some(handle),
+        }
+    }
+
+    /// Deletes the paste and disarms the guard.
+    pub async fn close(mut self) -> Result<()> {
+        match self.handle.take() {
+            Some(handle) => handle.delete().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Disarms the guard and returns the handle of the paste, so that the
+    /// paste is not deleted.
+    pub fn keep(mut self) -> PasteHandle<T> {
+        self.handle
+            .take()
+            .expect("ephemeral paste handle is only taken on consumption")
+    }
+}
+
+impl<T: Transport + 'static> From<PasteHandle<T>> for EphemeralPaste<T> {
+    fn from(value: PasteHandle<T>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Transport + 'static> Deref for EphemeralPaste<T> {
+    type Target = PasteHandle<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.handle
+            .as_ref()
+            .expect("ephemeral paste handle is only taken on consumption")
+    }
+}
+
+impl<T: Transport + 'static> Drop for EphemeralPaste<T> {
+    fn drop(&mut self) {
+        let Some(handle) = self.handle.take() else {
+            return;
+        };
+
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            log::warn!(
+                "ephemeral paste {} has not been deleted: no tokio runtime available on drop",
+                handle.id()
+            );
+            return;
+        };
+
+        let mut pending = PendingDelete {
+            id: handle.id().to_string(),
+            done: false,
+        };
+        runtime.spawn(async move {
+            let res = handle.delete().await;
+            pending.finish(res);
+        });
+    }
+}
+
+/// Tracks a deletion spawned on drop, so that it is logged if the task is
+/// dropped before completion, for example because the runtime shut down.
+struct PendingDelete {
+    id: String,
+    done: bool,
+}
+
+impl PendingDelete {
+    fn finish(&mut self, res: Result<()>) {
+        self.done = true;
+        if let Err(err) = res {
+            log::warn!("deleting ephemeral paste {} failed: {err}", self.id);
+        }
+    }
+}
+
+impl Drop for PendingDelete {
+    fn drop(&mut self) {
+        if !self.done {
+            log::warn!(
+                "ephemeral paste {} has not been deleted: the runtime shut down before \
+                 the deletion finished, call `close` to await it",
+                self.id
+            );
+        }
+    }
+}
+
+impl<T: Transport + Clone + 'static> UnauthenticatedClient<T> {
+    /// Creates a paste with the given content and metadata, which is
+    /// deleted when the returned `EphemeralPaste` guard is dropped or
+    /// closed.
+    ///
+    /// # Reference
+    /// Binds to the `POST /api/v2/pastes` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#unsecured-create-a-paste
+    pub async fn create_ephemeral_paste(
+        &self,
+        content: impl Into<String>,
+        metadata: Option<Metadata>,
+    ) -> Result<EphemeralPaste<T>> {
+        let handle = self.create_paste_handle(content, metadata).await?;
+        Ok(EphemeralPaste::new(handle))
+    }
+}
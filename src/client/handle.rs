@@ -0,0 +1,150 @@
+use super::{AuthenticatedClient, UnauthenticatedClient};
+#[cfg(feature = "reqwest")]
+use crate::transport::ReqwestTransport;
+use crate::{
+    errors::Result,
+    model::{Metadata, ModificationToken, Paste, PasteUpdate},
+    share::ShareUrl,
+    transport::Transport,
+};
+#[cfg(feature = "reqwest")]
+use serde::{de, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+
+/// A handle to a paste tying its ID to its modification token.
+///
+/// A `PasteHandle` can be obtained by creating a paste via
+/// `UnauthenticatedClient::create_paste_handle` or from an existing
+/// `AuthenticatedClient` via `PasteHandle::new`.
+///
+/// The handle serializes to the host URL, paste ID and modification
+/// token, so it can be stored and restored later. A deserialized handle
+/// uses a default `UnauthenticatedClient` for the stored host, which can
+/// be replaced via `with_client`. Deserializing requires the `reqwest`
+/// feature.
+///
+/// # Example
+/// ```no_run
+/// # use pasty_rs::client::*;
+/// # #[tokio::main]
+/// # async fn main() {
+/// let client = UnauthenticatedClient::new("https://pasty.lus.pm").unwrap();
+/// let handle = client.create_paste_handle("hello pasty!", None).await.unwrap();
+///
+/// let stored = serde_json::to_string(&handle).unwrap();
+/// let handle: PasteHandle = serde_json::from_str(&stored).unwrap();
+///
+/// let paste = handle.reload().await.unwrap();
+/// println!("{}: {}", handle.share_url(), paste.content);
+///
+/// handle.delete().await.unwrap();
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct PasteHandle<
+    #[cfg(feature = "reqwest")] T = ReqwestTransport,
+    #[cfg(not(feature = "reqwest"))] T,
+> {
+    client: AuthenticatedClient<T>,
+    id: String,
+}
+
+impl<T: Transport> PasteHandle<T> {
+    /// Creates a new handle for the paste with the given ID using the
+    /// given client, which must be authenticated with the modification
+    /// token of that paste.
+    pub fn new(client: AuthenticatedClient<T>, id: impl Into<String>) -> Self {
+        Self {
+            client,
+            id: id.into(),
+        }
+    }
+
+    /// Returns the ID of the paste.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the modification token of the paste.
+    pub fn token(&self) -> &ModificationToken {
+        self.client.token()
+    }
+
+    /// Returns the authenticated client used by this handle.
+    pub fn client(&self) -> &AuthenticatedClient<T> {
+        &self.client
+    }
+
+    /// Returns a `ShareUrl` linking to the paste in the pasty web
+    /// frontend.
+    pub fn share_url(&self) -> ShareUrl {
+        self.client.inner().share_url(&self.id)
+    }
+
+    /// Retrieves the current state of the paste.
+    pub async fn reload(&self) -> Result<Paste> {
+        self.client.inner().paste(&self.id).await
+    }
+
+    /// Partially updates the paste with the given `PasteUpdate`.
+    pub async fn update(&self, update: &PasteUpdate) -> Result<()> {
+        self.client.update_paste_with(&self.id, update).await
+    }
+
+    /// Updates the content and metadata of the paste.
+    pub async fn update_content(
+        &self,
+        content: impl Into<String>,
+        metadata: Option<Metadata>,
+    ) -> Result<()> {
+        self.client.update_paste(&self.id, content, metadata).await
+    }
+
+    /// Deletes the paste and consumes the handle.
+    pub async fn delete(self) -> Result<()> {
+        self.client.delete_paste(&self.id).await
+    }
+
+    /// Returns a handle to the same paste which performs its requests
+    /// with the given client.
+    pub fn with_client<U: Transport>(self, client: UnauthenticatedClient<U>) -> PasteHandle<U> {
+        PasteHandle {
+            client: client.authenticate(self.client.token),
+            id: self.id,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct HandleState<'a> {
+    #[serde(borrow)]
+    host: Cow<'a, str>,
+    #[serde(borrow)]
+    id: Cow<'a, str>,
+    #[serde(borrow)]
+    token: Cow<'a, str>,
+}
+
+impl<T> Serialize for PasteHandle<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        HandleState {
+            host: self.client.client.state.host.as_str().into(),
+            id: self.id.as_str().into(),
+            token: self.client.token.expose().into(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl<'de> Deserialize<'de> for PasteHandle {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let state = HandleState::deserialize(deserializer)?;
+        let client = UnauthenticatedClient::new(state.host.as_ref()).map_err(de::Error::custom)?;
+        Ok(Self::new(
+            client.authenticate(state.token.into_owned()),
+            state.id.into_owned(),
+        ))
+    }
+}
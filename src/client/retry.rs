@@ -0,0 +1,235 @@
+use crate::{
+    errors::Result,
+    transport::{
+        header::{HeaderMap, RETRY_AFTER},
+        HttpResponse, Method, StatusCode,
+    },
+};
+use std::time::{Duration, SystemTime};
+
+/// Policy defining if and how failed requests are retried.
+///
+/// Requests are retried on connection errors and timeouts, on `5xx`
+/// server errors and on `429 Too Many Requests` responses. If the
+/// response carries a `Retry-After` header, the requested delay is
+/// honored, unless it exceeds `max_backoff`, in which case the request
+/// is not retried at all. Otherwise, the delay between attempts grows
+/// exponentially starting at `initial_backoff`, capped at `max_backoff`.
+///
+/// Non-idempotent requests, i.e. `POST` requests like `create_paste`,
+/// are never retried unless `retry_non_idempotent` is enabled, because
+/// a request failing after the server processed it would otherwise
+/// create a duplicate paste.
+///
+/// The default policy does not retry at all.
+///
+/// # Example
+/// ```
+/// # use pasty_rs::client::*;
+/// # use std::time::Duration;
+/// let client = UnauthenticatedClient::new("https://pasty.lus.pm")
+///     .unwrap()
+///     .with_retry_policy(RetryPolicy::new(3).initial_backoff(Duration::from_millis(200)));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy retrying failed requests up to `max_retries`
+    /// times with an initial backoff of 500ms and a maximum backoff of
+    /// 30s.
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            retry_non_idempotent: false,
+        }
+    }
+
+    /// Creates a policy which never retries failed requests.
+    pub fn none() -> Self {
+        Self::new(0)
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Sets the maximum delay between two attempts.
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Sets whether non-idempotent requests, like creating a paste,
+    /// should be retried as well.
+    pub fn retry_non_idempotent(mut self, retry: bool) -> Self {
+        self.retry_non_idempotent = retry;
+        self
+    }
+
+    /// Returns whether a request with the given method may be retried
+    /// after the given number of already performed retries.
+    pub(crate) fn allows_retry(&self, method: &Method, retries: u32) -> bool {
+        retries < self.max_retries && (self.retry_non_idempotent || method != Method::POST)
+    }
+
+    /// Returns the delay before the next attempt after the given number
+    /// of already performed retries.
+    pub(crate) fn backoff(&self, retries: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retries))
+            .min(self.max_backoff)
+    }
+
+    /// Returns the delay before the next attempt after the given
+    /// result of a request, or `None` if the request should not be
+    /// retried.
+    ///
+    /// Responses are retried on `5xx` and `429` status codes and errors
+    /// are retried if they are connection errors or timeouts. Responses
+    /// requesting a `Retry-After` delay longer than `max_backoff` are not
+    /// retried.
+    pub(crate) fn delay(&self, res: &Result<HttpResponse>, retries: u32) -> Option<Duration> {
+        match res {
+            Ok(res) => {
+                if !res.status.is_server_error() && res.status != StatusCode::TOO_MANY_REQUESTS {
+                    return None;
+                }
+                match retry_after(&res.headers) {
+                    Some(delay) if delay > self.max_backoff => None,
+                    Some(delay) => Some(delay),
+                    None => Some(self.backoff(retries)),
+                }
+            }
+            Err(err) => err.is_retryable().then(|| self.backoff(retries)),
+        }
+    }
+}
+
+/// Parses the `Retry-After` header, which is either given in seconds or
+/// as HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = value.parse() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: StatusCode, retry_after: Option<&str>) -> Result<HttpResponse> {
+        let mut res = HttpResponse::new(status, "");
+        if let Some(retry_after) = retry_after {
+            res.headers
+                .insert(RETRY_AFTER, retry_after.parse().unwrap());
+        }
+        Ok(res)
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_max() {
+        let policy = RetryPolicy::new(10)
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_secs(1));
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff(4), Duration::from_secs(1));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn allows_retry_respects_max_retries_and_method() {
+        let policy = RetryPolicy::new(2);
+        assert!(policy.allows_retry(&Method::GET, 1));
+        assert!(!policy.allows_retry(&Method::GET, 2));
+        assert!(!policy.allows_retry(&Method::POST, 0));
+        assert!(policy
+            .retry_non_idempotent(true)
+            .allows_retry(&Method::POST, 0));
+        assert!(!RetryPolicy::none().allows_retry(&Method::GET, 0));
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_and_dates() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, " 120 ".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+
+        let date = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(60));
+        headers.insert(RETRY_AFTER, date.parse().unwrap());
+        let delay = retry_after(&headers).unwrap();
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+
+        headers.insert(
+            RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+
+        headers.insert(RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn delay_depends_on_response() {
+        let policy = RetryPolicy::new(3)
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_secs(10));
+
+        assert_eq!(policy.delay(&response(StatusCode::OK, None), 0), None);
+        assert_eq!(
+            policy.delay(&response(StatusCode::NOT_FOUND, None), 0),
+            None
+        );
+        assert_eq!(
+            policy.delay(&response(StatusCode::SERVICE_UNAVAILABLE, None), 1),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(
+            policy.delay(&response(StatusCode::TOO_MANY_REQUESTS, Some("3")), 0),
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[test]
+    fn delay_gives_up_when_retry_after_exceeds_max_backoff() {
+        let policy = RetryPolicy::new(3).max_backoff(Duration::from_secs(30));
+
+        assert_eq!(
+            policy.delay(&response(StatusCode::TOO_MANY_REQUESTS, Some("86400")), 0),
+            None
+        );
+        assert_eq!(
+            policy.delay(&response(StatusCode::TOO_MANY_REQUESTS, Some("30")), 0),
+            Some(Duration::from_secs(30))
+        );
+    }
+}
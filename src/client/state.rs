@@ -0,0 +1,166 @@
+use super::{PasteCache, RetryPolicy};
+use crate::{
+    disk_cache::{unix_now, DiskCache},
+    errors::{Error, Result},
+    model::{ApplicationInformation, CachedPaste, ModificationToken, Paste},
+    token_store::TokenStore,
+};
+use std::sync::{Arc, OnceLock};
+use url::Url;
+
+/// The configuration and caches of a client.
+///
+/// The state does not perform any requests, so that it is shared by the
+/// async and the blocking clients, which only add the I/O around it.
+#[derive(Clone)]
+pub(super) struct ClientState {
+    pub(super) host: Url,
+    pub(super) retry_policy: RetryPolicy,
+    pub(super) token_store: Option<Arc<dyn TokenStore>>,
+    pub(super) capabilities: Arc<OnceLock<ApplicationInformation>>,
+    pub(super) capability_checks: bool,
+    pub(super) cache: Option<Arc<PasteCache>>,
+    pub(super) disk_cache: Option<Arc<DiskCache>>,
+}
+
+impl ClientState {
+    pub(super) fn new(host: Url) -> Self {
+        Self {
+            host,
+            retry_policy: RetryPolicy::none(),
+            token_store: None,
+            capabilities: Arc::default(),
+            capability_checks: false,
+            cache: None,
+            disk_cache: None,
+        }
+    }
+
+    /// Returns the paste with the given ID from the in-memory cache, if
+    /// set and cached.
+    pub(super) fn cached(&self, id: &str) -> Option<Paste> {
+        self.cache.as_ref()?.get(id)
+    }
+
+    /// Returns `true` if an in-memory cache is set, which requires the
+    /// capabilities to cap the ttl of cached pastes.
+    pub(super) fn caches_pastes(&self) -> bool {
+        self.cache.is_some()
+    }
+
+    /// Caches a requested paste in the in-memory and disk caches, if set.
+    ///
+    /// The ttl of the in-memory cache is only capped by the paste lifetime
+    /// if the capabilities are passed.
+    pub(super) fn cache_paste(&self, paste: &Paste, info: Option<&ApplicationInformation>) {
+        if let Some(cache) = &self.cache {
+            let expires_at = info.and_then(|info| paste.expires_at(info));
+            cache.insert(paste.clone(), expires_at);
+        }
+        if let Some(disk_cache) = &self.disk_cache {
+            if let Err(err) = disk_cache.set(&self.host, paste) {
+                log::warn!("failed caching paste {} on disk: {err}", paste.id);
+            }
+        }
+    }
+
+    /// Invalidates the cached paste with the given ID if requesting it
+    /// failed because it does not exist anymore.
+    pub(super) fn request_failed(&self, id: &str, err: &Error) {
+        if err.is_not_found() {
+            self.invalidate_cached(id);
+        }
+    }
+
+    /// Removes the paste with the given ID from the in-memory and disk
+    /// caches, if set.
+    pub(super) fn invalidate_cached(&self, id: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(id);
+        }
+        if let Some(disk_cache) = &self.disk_cache {
+            if let Err(err) = disk_cache.remove(&self.host, id) {
+                log::warn!("failed removing paste {id} from disk cache: {err}");
+            }
+        }
+    }
+
+    /// Wraps the result of requesting the paste with the given ID into a
+    /// `CachedPaste`, falling back to the disk cache if the pasty instance
+    /// is unreachable.
+    pub(super) fn with_fallback(&self, id: &str, res: Result<Paste>) -> Result<CachedPaste> {
+        let err = match res {
+            Ok(paste) => {
+                return Ok(CachedPaste {
+                    paste,
+                    stale: false,
+                    fetched: unix_now(),
+                })
+            }
+            Err(err) if is_unreachable(&err) => err,
+            Err(err) => return Err(err),
+        };
+
+        let Some(disk_cache) = &self.disk_cache else {
+            return Err(err);
+        };
+        match disk_cache.get(&self.host, id) {
+            Ok(Some(paste)) => Ok(paste),
+            Ok(None) => Err(err),
+            Err(cache_err) => {
+                log::warn!("failed reading paste {id} from disk cache: {cache_err}");
+                Err(err)
+            }
+        }
+    }
+
+    /// Records the modification token of a created paste in the token
+    /// store, if set.
+    pub(super) fn store_token(&self, id: &str, token: &ModificationToken) {
+        if let Some(token_store) = &self.token_store {
+            if let Err(err) = token_store.set(&self.host, id, token.expose()) {
+                log::warn!("failed storing modification token of paste {id}: {err}");
+            }
+        }
+    }
+
+    /// Removes the modification token of a deleted paste from the token
+    /// store, if set.
+    pub(super) fn remove_token(&self, id: &str) {
+        if let Some(token_store) = &self.token_store {
+            if let Err(err) = token_store.remove(&self.host, id) {
+                log::warn!("failed removing modification token of paste {id}: {err}");
+            }
+        }
+    }
+
+    /// Returns the modification token recorded in the token store for the
+    /// paste with the given ID.
+    ///
+    /// Returns `Error::MissingToken` if no token store is set or no token
+    /// is stored for the paste.
+    pub(super) fn stored_token(&self, id: &str) -> Result<ModificationToken> {
+        let token = match &self.token_store {
+            Some(token_store) => token_store.get(&self.host, id)?,
+            None => None,
+        };
+        token
+            .map(ModificationToken::from)
+            .ok_or_else(|| Error::MissingToken(id.to_string()))
+    }
+}
+
+/// Returns `Error::Unsupported` for the given feature if it is not
+/// supported by the pasty instance.
+pub(super) fn ensure_supported(feature: &'static str, supported: bool) -> Result<()> {
+    if !supported {
+        return Err(Error::Unsupported(feature));
+    }
+    Ok(())
+}
+
+/// Returns `true` if the given error indicates that the pasty instance is
+/// unreachable, i.e. on connection errors, timeouts and `5xx` errors.
+fn is_unreachable(err: &Error) -> bool {
+    err.is_retryable() || err.status().is_some_and(|status| status.is_server_error())
+}
@@ -0,0 +1,227 @@
+//! Persistent on-disk cache of pastes for offline reading.
+//!
+//! When a `DiskCache` is attached to a client via
+//! `UnauthenticatedClient::with_disk_cache`, every paste requested via
+//! `paste` is written to the cache. `paste_with_fallback` then serves
+//! previously fetched pastes from the cache when the pasty instance is
+//! unreachable and flags them as stale.
+//!
+//! Each paste is stored in its own file, named after a hash of the host
+//! URL and the paste ID. When the total size of the cache exceeds its
+//! limit, the least recently used files are evicted.
+//!
+//! # Example
+//! ```no_run
+//! # use pasty_rs::{client::*, disk_cache::DiskCache};
+//! # #[tokio::main]
+//! # async fn main() {
+//! let cache = DiskCache::new(DiskCache::default_dir().unwrap());
+//! let client = UnauthenticatedClient::new("https://pasty.lus.pm")
+//!     .unwrap()
+//!     .with_disk_cache(cache);
+//!
+//! let paste = client.paste_with_fallback("abcdef").await.unwrap();
+//! if paste.stale {
+//!     eprintln!("pasty is unreachable, showing a cached version");
+//! }
+//! println!("{}", paste.paste.content);
+//! # }
+//! ```
+
+use crate::{
+    errors::Result,
+    model::{CachedPaste, Paste},
+    token_store::{host_key, write_atomic},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    env,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use url::Url;
+
+/// The default maximum size of a `DiskCache` of 64 MiB.
+pub const DEFAULT_MAX_SIZE: u64 = 64 * 1024 * 1024;
+
+/// A cache persisting pastes as files in a directory.
+///
+/// The files are read and written on every access, so multiple processes
+/// can share the same directory. Writes replace files atomically and, on
+/// unix systems, the files are only readable by the current user.
+pub struct DiskCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    host: String,
+    fetched: usize,
+    paste: Paste,
+}
+
+impl DiskCache {
+    /// Creates a new `DiskCache` using the given directory, which is
+    /// created on the first write, if not existent.
+    ///
+    /// The cache is limited to `DEFAULT_MAX_SIZE` bytes.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_size: DEFAULT_MAX_SIZE,
+        }
+    }
+
+    /// Sets the maximum total size of the cached files in bytes.
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Returns the default location of the cache directory, which is
+    /// `$XDG_CACHE_HOME/pasty-rs/pastes`, falling back to
+    /// `$HOME/.cache/pasty-rs/pastes`.
+    pub fn default_dir() -> Option<PathBuf> {
+        let cache_dir = env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+        Some(cache_dir.join("pasty-rs").join("pastes"))
+    }
+
+    /// Returns the path of the cache directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the maximum total size of the cached files in bytes.
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// Returns the cached paste with the given ID on the given host, if
+    /// existent. The returned paste is flagged as stale.
+    pub fn get(&self, host: &Url, id: &str) -> Result<Option<CachedPaste>> {
+        let path = self.path(host, id);
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let entry: Entry = serde_json::from_slice(&data)?;
+        if entry.host != host_key(host) || entry.paste.id != id {
+            return Ok(None);
+        }
+
+        // Mark the file as recently used, so that it is evicted last. This
+        // is best-effort, because the cache might be read-only.
+        let _ = File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+
+        Ok(Some(CachedPaste {
+            paste: entry.paste,
+            stale: true,
+            fetched: entry.fetched,
+        }))
+    }
+
+    /// Stores the given paste of the given host and evicts the least
+    /// recently used pastes if the cache exceeds its maximum size.
+    pub fn set(&self, host: &Url, paste: &Paste) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let entry = Entry {
+            host: host_key(host),
+            fetched: unix_now(),
+            paste: paste.clone(),
+        };
+        write_atomic(&self.path(host, &paste.id), &serde_json::to_vec(&entry)?)?;
+
+        self.evict()
+    }
+
+    /// Removes the cached paste with the given ID on the given host, if
+    /// existent.
+    pub fn remove(&self, host: &Url, id: &str) -> Result<()> {
+        match fs::remove_file(self.path(host, id)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes all cached pastes.
+    pub fn clear(&self) -> Result<()> {
+        for (path, _, _) in self.files()? {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    fn path(&self, host: &Url, id: &str) -> PathBuf {
+        let hash = fnv1a(format!("{}\n{id}", host_key(host)).as_bytes());
+        self.dir.join(format!("{hash:016x}.json"))
+    }
+
+    /// Returns the path, size and modification time of all cache files.
+    fn files(&self) -> Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut files = Vec::new();
+        for entry in dir {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let meta = entry.metadata()?;
+                files.push((path, meta.len(), meta.modified()?));
+            }
+        }
+        Ok(files)
+    }
+
+    fn evict(&self) -> Result<()> {
+        let mut files = self.files()?;
+        let mut size: u64 = files.iter().map(|(_, len, _)| len).sum();
+        if size <= self.max_size {
+            return Ok(());
+        }
+
+        files.sort_by_key(|(_, _, modified)| *modified);
+        for (path, len, _) in files {
+            if size <= self.max_size {
+                break;
+            }
+            match fs::remove_file(&path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+                _ => size -= len,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the 64 bit FNV-1a hash of the given data, which, in contrast
+/// to the hashers of the standard library, is stable across releases.
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Returns the current unix timestamp in seconds.
+pub(crate) fn unix_now() -> usize {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as usize)
+        .unwrap_or_default()
+}
@@ -0,0 +1,244 @@
+//! Client-side paste encryption compatible with the pasty web frontend.
+//!
+//! This module is only available with the `encryption` feature enabled.
+//!
+//! Encrypted pastes use AES-GCM with a 256 bit key and a random 96 bit IV.
+//! The paste content is the standard base64 encoded ciphertext (including
+//! the authentication tag) and the IV is stored base64 encoded in the
+//! `pf_encryption` metadata of the paste. The key itself is never sent to
+//! the pasty instance. Instead, it is carried in the fragment of the
+//! frontend URL of the paste (`https://host/{id}#{key}`).
+//!
+//! # Example
+//! ```
+//! # use pasty_rs::{encryption::EncryptionKey, model::*};
+//! let key = EncryptionKey::generate();
+//! let (content, pf_encryption) = key.encrypt("hello pasty!").unwrap();
+//!
+//! let paste = Paste {
+//!     id: "abcdef".into(),
+//!     content,
+//!     created: 0,
+//!     metadata: Some(Metadata {
+//!         pf_encryption: Some(pf_encryption),
+//!         ..Default::default()
+//!     }),
+//! };
+//!
+//! let key: EncryptionKey = key.to_string().parse().unwrap();
+//! assert_eq!(paste.decrypt(&key).unwrap(), "hello pasty!");
+//! ```
+
+use crate::{
+    client::UnauthenticatedClient,
+    errors::{Error, Result},
+    model::{CreatedPaste, Metadata, Paste, PfEncryption},
+    transport::Transport,
+};
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use base64::{
+    engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use std::{fmt, str::FromStr};
+use zeroize::Zeroize;
+
+/// The algorithm identifier set in the `pf_encryption` metadata.
+pub const ALGORITHM: &str = "AES-GCM";
+
+/// A 256 bit AES-GCM key used to encrypt and decrypt paste contents.
+///
+/// The key is displayed as padded standard base64, which is the
+/// representation the frontend uses in the URL fragment of its links and
+/// decodes via `atob`. When parsed, URL-safe base64 and missing padding
+/// are accepted as well.
+///
+/// The key bytes are zeroed when the key is dropped.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    /// Generates a new random key.
+    pub fn generate() -> Self {
+        Self(Aes256Gcm::generate_key(OsRng).into())
+    }
+
+    /// Creates a key from the given raw key bytes.
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the raw key bytes.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Encrypts the given plaintext with a random IV.
+    ///
+    /// Returns the base64 encoded ciphertext, which should be used as
+    /// paste content, and the `PfEncryption` metadata required to
+    /// decrypt it again.
+    pub fn encrypt(&self, plaintext: &str) -> Result<(String, PfEncryption)> {
+        let nonce = Aes256Gcm::generate_nonce(OsRng);
+        let ciphertext = self
+            .cipher()
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| Error::Encryption("encrypting content failed".into()))?;
+
+        let pf_encryption = PfEncryption {
+            alg: ALGORITHM.into(),
+            iv: STANDARD.encode(nonce),
+        };
+
+        Ok((STANDARD.encode(ciphertext), pf_encryption))
+    }
+
+    /// Decrypts the given base64 encoded ciphertext using the IV from
+    /// the passed `PfEncryption` metadata.
+    pub fn decrypt(&self, ciphertext: &str, pf_encryption: &PfEncryption) -> Result<String> {
+        if !pf_encryption.alg.eq_ignore_ascii_case(ALGORITHM) {
+            return Err(Error::Encryption(format!(
+                "unsupported algorithm: {}",
+                pf_encryption.alg
+            )));
+        }
+
+        let iv = decode_base64(&pf_encryption.iv)?;
+        if iv.len() != 12 {
+            return Err(Error::Encryption("invalid iv length".into()));
+        }
+
+        let plaintext = self
+            .cipher()
+            .decrypt(
+                Nonce::from_slice(&iv),
+                decode_base64(ciphertext)?.as_slice(),
+            )
+            .map_err(|_| Error::Encryption("decrypting content failed".into()))?;
+
+        String::from_utf8(plaintext)
+            .map_err(|_| Error::Encryption("decrypted content is not valid utf-8".into()))
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(&self.0.into())
+    }
+}
+
+impl fmt::Display for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&STANDARD.encode(self.0))
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(<redacted>)")
+    }
+}
+
+impl Drop for EncryptionKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl FromStr for EncryptionKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        decode_base64(s)?
+            .try_into()
+            .map(Self)
+            .map_err(|_| Error::Encryption("invalid key length".into()))
+    }
+}
+
+impl Paste {
+    /// Returns `true` if the paste carries `pf_encryption` metadata.
+    pub fn is_encrypted(&self) -> bool {
+        self.pf_encryption().is_some()
+    }
+
+    /// Decrypts the content of the paste with the given key.
+    ///
+    /// Returns an `Error::Encryption` if the paste is not encrypted, was
+    /// encrypted with an unsupported algorithm or the key is wrong.
+    pub fn decrypt(&self, key: &EncryptionKey) -> Result<String> {
+        let pf_encryption = self
+            .pf_encryption()
+            .ok_or_else(|| Error::Encryption("paste is not encrypted".into()))?;
+        key.decrypt(&self.content, pf_encryption)
+    }
+
+    fn pf_encryption(&self) -> Option<&PfEncryption> {
+        self.metadata.as_ref()?.pf_encryption.as_ref()
+    }
+}
+
+impl<T: Transport> UnauthenticatedClient<T> {
+    /// Encrypts the given content with a newly generated key and creates
+    /// a paste with it.
+    ///
+    /// Returns the created paste alongside the key required to decrypt
+    /// it. The key is not sent to the pasty instance.
+    ///
+    /// # Reference
+    /// Binds to the `POST /api/v2/pastes` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#unsecured-create-a-paste
+    pub async fn create_encrypted_paste(
+        &self,
+        content: impl AsRef<str>,
+    ) -> Result<(CreatedPaste, EncryptionKey)> {
+        let key = EncryptionKey::generate();
+        let (content, metadata) = encrypted_content(&key, content.as_ref())?;
+        let paste = self.create_paste(content, Some(metadata)).await?;
+        Ok((paste, key))
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<T: crate::transport::BlockingTransport> crate::client::blocking::UnauthenticatedClient<T> {
+    /// Encrypts the given content with a newly generated key and creates
+    /// a paste with it.
+    ///
+    /// Returns the created paste alongside the key required to decrypt
+    /// it. The key is not sent to the pasty instance.
+    ///
+    /// # Reference
+    /// Binds to the `POST /api/v2/pastes` endpoint.
+    /// https://github.com/lus/pasty/blob/master/API.md#unsecured-create-a-paste
+    pub fn create_encrypted_paste(
+        &self,
+        content: impl AsRef<str>,
+    ) -> Result<(CreatedPaste, EncryptionKey)> {
+        let key = EncryptionKey::generate();
+        let (content, metadata) = encrypted_content(&key, content.as_ref())?;
+        let paste = self.create_paste(content, Some(metadata))?;
+        Ok((paste, key))
+    }
+}
+
+fn encrypted_content(key: &EncryptionKey, content: &str) -> Result<(String, Metadata)> {
+    let (content, pf_encryption) = key.encrypt(content)?;
+    let metadata = Metadata {
+        pf_encryption: Some(pf_encryption),
+        ..Default::default()
+    };
+    Ok((content, metadata))
+}
+
+/// Decodes base64 regardless of alphabet (standard or URL-safe) and
+/// padding.
+fn decode_base64(s: &str) -> Result<Vec<u8>> {
+    let s = s.trim_end_matches('=');
+    let res = if s.contains(['-', '_']) {
+        URL_SAFE_NO_PAD.decode(s)
+    } else {
+        STANDARD_NO_PAD.decode(s)
+    };
+    res.map_err(|_| Error::Encryption("invalid base64 encoding".into()))
+}
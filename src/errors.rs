@@ -1,12 +1,147 @@
+use crate::{model::ErrorResponse, transport::StatusCode};
+use std::convert::Infallible;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Error, Debug)]
 pub enum Error {
+    #[cfg(feature = "reqwest")]
     #[error(transparent)]
     Reqwest(#[from] reqwest::Error),
 
     #[error("parsing url: {0}")]
     UrlParse(#[from] url::ParseError),
+
+    #[error("json: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("invalid header value: {0}")]
+    InvalidHeaderValue(#[from] http::header::InvalidHeaderValue),
+
+    #[error("transport: {source}")]
+    Transport {
+        source: Box<dyn std::error::Error + Send + Sync>,
+        retryable: bool,
+    },
+
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("no modification token stored for paste {0}")]
+    MissingToken(String),
+
+    #[error("metadata must serialize to a JSON object")]
+    InvalidMetadata,
+
+    #[error("{0} not supported by this pasty instance")]
+    Unsupported(&'static str),
+
+    #[error("api error ({status}): {message}")]
+    Api {
+        status: StatusCode,
+        message: String,
+        body: String,
+    },
+
+    #[error("invalid paste id: {0}")]
+    InvalidPasteId(String),
+
+    #[error("invalid share url: {0}")]
+    InvalidShareUrl(String),
+
+    #[cfg(feature = "encryption")]
+    #[error("encryption: {0}")]
+    Encryption(String),
+}
+
+impl From<Infallible> for Error {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
+
+impl Error {
+    /// Creates an `Error::Api` from the given response status code and
+    /// raw response body.
+    ///
+    /// If the body is a pasty JSON error response, its message is used.
+    /// Otherwise, the canonical reason of the status code is used as
+    /// message.
+    pub(crate) fn from_response(status: StatusCode, body: String) -> Self {
+        let message = serde_json::from_str::<ErrorResponse>(&body)
+            .map(|r| r.message)
+            .unwrap_or_else(|_| status.canonical_reason().unwrap_or_default().to_string());
+
+        Self::Api {
+            status,
+            message,
+            body,
+        }
+    }
+
+    /// Creates an `Error::Transport` from the given error of a custom
+    /// `Transport`.
+    ///
+    /// `retryable` should be `true` for errors like connection failures
+    /// or timeouts, which may be resolved by retrying the request.
+    pub fn transport(
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+        retryable: bool,
+    ) -> Self {
+        Self::Transport {
+            source: source.into(),
+            retryable,
+        }
+    }
+
+    /// Returns `true` if the error is a connection error or timeout,
+    /// which may be resolved by retrying the request.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            #[cfg(feature = "reqwest")]
+            Self::Reqwest(err) => err.is_connect() || err.is_timeout(),
+            Self::Transport { retryable, .. } => *retryable,
+            _ => false,
+        }
+    }
+
+    /// Returns the HTTP status code of the response if this is an
+    /// `Error::Api` or a `Error::Reqwest` caused by a response status.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Api { status, .. } => Some(*status),
+            #[cfg(feature = "reqwest")]
+            Self::Reqwest(err) => err.status(),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the requested resource, i.e. the paste, could
+    /// not be found (`404 Not Found`).
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(StatusCode::NOT_FOUND)
+    }
+
+    /// Returns `true` if the request was rejected because the passed
+    /// token is missing or invalid (`401 Unauthorized` or
+    /// `403 Forbidden`).
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(
+            self.status(),
+            Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN)
+        )
+    }
+
+    /// Returns `true` if the request has been rate limited by the pasty
+    /// instance (`429 Too Many Requests`).
+    pub fn is_rate_limited(&self) -> bool {
+        self.status() == Some(StatusCode::TOO_MANY_REQUESTS)
+    }
+
+    /// Returns `true` if the requested operation is not supported by the
+    /// pasty instance according to its application information.
+    pub fn is_unsupported(&self) -> bool {
+        matches!(self, Self::Unsupported(_))
+    }
 }
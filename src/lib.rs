@@ -1,3 +1,15 @@
 pub mod model;
 pub mod client;
+pub mod disk_cache;
 pub mod errors;
+pub mod share;
+pub mod token_store;
+pub mod transport;
+
+#[cfg(feature = "encryption")]
+pub mod encryption;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "reqwest")]
+pub use reqwest;
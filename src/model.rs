@@ -1,6 +1,13 @@
-use serde::{Deserialize, Serialize};
+use crate::errors::{Error, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use zeroize::Zeroize;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
 pub struct ApplicationInformation {
     #[serde(rename = "modificationTokens")]
     pub modification_tokens: bool,
@@ -10,35 +17,428 @@ pub struct ApplicationInformation {
     pub version: String,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+impl ApplicationInformation {
+    /// Returns the duration after which pastes are deleted by the pasty
+    /// instance, or `None` if pastes never expire.
+    ///
+    /// pasty reports the lifetime in milliseconds and uses `-1` (or any
+    /// other non-positive value) when the automatic deletion of pastes is
+    /// disabled.
+    pub fn paste_lifetime_duration(&self) -> Option<Duration> {
+        u64::try_from(self.paste_lifetime)
+            .ok()
+            .filter(|&ms| ms > 0)
+            .map(Duration::from_millis)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
 pub struct PfEncryption {
     pub alg: String,
     pub iv: String,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+/// Metadata of a paste.
+///
+/// Besides the `pf_encryption` key used by the pasty web frontend, any
+/// other keys are kept in `extra`, so that reading and writing back the
+/// metadata of a paste never loses keys set by other tools.
+///
+/// # Example
+/// ```
+/// # use pasty_rs::model::Metadata;
+/// let mut metadata = Metadata::default();
+/// metadata.set("build", &1234).unwrap();
+///
+/// assert_eq!(metadata.get::<u32>("build").unwrap(), Some(1234));
+/// assert_eq!(metadata.get::<u32>("commit").unwrap(), None);
+/// ```
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug, Default)]
 pub struct Metadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub pf_encryption: Option<PfEncryption>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct Paste {
+impl Metadata {
+    const PF_ENCRYPTION: &'static str = "pf_encryption";
+
+    /// Returns the value of the given key deserialized into `T`, or
+    /// `None` if the key is not set.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let value = if key == Self::PF_ENCRYPTION {
+            match &self.pf_encryption {
+                Some(pf_encryption) => serde_json::to_value(pf_encryption)?,
+                None => return Ok(None),
+            }
+        } else {
+            match self.extra.get(key) {
+                Some(value) => value.clone(),
+                None => return Ok(None),
+            }
+        };
+
+        Ok(Some(serde_json::from_value(value)?))
+    }
+
+    /// Sets the given key to the given value serialized as JSON.
+    pub fn set<T: Serialize + ?Sized>(&mut self, key: impl Into<String>, value: &T) -> Result<()> {
+        let key = key.into();
+        let value = serde_json::to_value(value)?;
+
+        if key == Self::PF_ENCRYPTION {
+            self.pf_encryption = serde_json::from_value(value)?;
+        } else {
+            self.extra.insert(key, value);
+        }
+
+        Ok(())
+    }
+
+    /// Removes the given key and returns its previous value, if set.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        if key == Self::PF_ENCRYPTION {
+            return self
+                .pf_encryption
+                .take()
+                .and_then(|v| serde_json::to_value(v).ok());
+        }
+
+        self.extra.remove(key)
+    }
+
+    /// Returns `true` if the given key is set.
+    pub fn contains_key(&self, key: &str) -> bool {
+        if key == Self::PF_ENCRYPTION {
+            return self.pf_encryption.is_some();
+        }
+
+        self.extra.contains_key(key)
+    }
+}
+
+/// A paste as returned by the pasty API.
+///
+/// The metadata of the paste is deserialized into `M`, which defaults to
+/// `Metadata` but can be any user defined serde type.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct Paste<M = Metadata> {
     pub id: String,
     pub content: String,
     pub created: usize,
-    pub metadata: Option<Metadata>,
+    pub metadata: Option<M>,
+}
+
+impl<M> Paste<M> {
+    /// Returns the point in time at which the paste has been created.
+    ///
+    /// # Example
+    /// ```
+    /// # use pasty_rs::model::*;
+    /// # use std::time::{Duration, UNIX_EPOCH};
+    /// let paste: Paste = Paste {
+    ///     id: "abcdef".into(),
+    ///     content: "hello pasty!".into(),
+    ///     created: 1_700_000_000,
+    ///     metadata: None,
+    /// };
+    ///
+    /// let info = ApplicationInformation {
+    ///     modification_tokens: true,
+    ///     paste_lifetime: 3 * 24 * 60 * 60 * 1000,
+    ///     reports: false,
+    ///     version: "v0.0.0".into(),
+    /// };
+    ///
+    /// let expires_at = paste.expires_at(&info).unwrap();
+    /// assert_eq!(
+    ///     expires_at.duration_since(paste.created_at()).unwrap(),
+    ///     Duration::from_secs(3 * 24 * 60 * 60),
+    /// );
+    /// ```
+    pub fn created_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.created as u64)
+    }
+
+    /// Returns the point in time at which the paste has been created as
+    /// `time::OffsetDateTime` in UTC.
+    ///
+    /// This method is only available with the `time` feature enabled.
+    #[cfg(feature = "time")]
+    pub fn created_at_time(&self) -> time::OffsetDateTime {
+        self.created_at().into()
+    }
+
+    /// Returns the point in time at which the paste has been created as
+    /// `chrono::DateTime` in UTC.
+    ///
+    /// This method is only available with the `chrono` feature enabled.
+    #[cfg(feature = "chrono")]
+    pub fn created_at_chrono(&self) -> chrono::DateTime<chrono::Utc> {
+        self.created_at().into()
+    }
+
+    /// Returns the point in time at which the paste will be deleted by
+    /// the pasty instance with the given application information, or
+    /// `None` if pastes never expire on that instance.
+    pub fn expires_at(&self, info: &ApplicationInformation) -> Option<SystemTime> {
+        Some(self.created_at() + info.paste_lifetime_duration()?)
+    }
+
+    /// Returns `true` if the paste has outlived the paste lifetime of the
+    /// pasty instance with the given application information.
+    pub fn is_expired(&self, info: &ApplicationInformation) -> bool {
+        self.expires_at(info)
+            .is_some_and(|expires_at| expires_at <= SystemTime::now())
+    }
+}
+
+/// A paste which might have been served from a `DiskCache` because the
+/// pasty instance was unreachable.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct CachedPaste<M = Metadata> {
+    #[serde(flatten)]
+    pub paste: Paste<M>,
+    /// `true` if the paste has been served from the cache and might be
+    /// outdated.
+    pub stale: bool,
+    /// The unix timestamp in seconds at which the paste has been fetched
+    /// from the pasty instance.
+    pub fetched: usize,
 }
 
-#[derive(Serialize, Debug)]
-pub struct CreatePasteRequest {
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct CreatePasteRequest<M = Metadata> {
     pub content: String,
-    pub metadata: Option<Metadata>,
+    pub metadata: Option<M>,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct CreatedPaste {
+/// A partial update of a paste.
+///
+/// Only the set fields are sent to the pasty instance. The metadata keys
+/// are merged into the existing metadata of the paste, where keys set to
+/// `null` are removed.
+///
+/// # Example
+/// ```
+/// # use pasty_rs::model::PasteUpdate;
+/// let update = PasteUpdate::new()
+///     .set_metadata_key("build", &1234)
+///     .unwrap()
+///     .remove_metadata_key("draft");
+///
+/// assert_eq!(
+///     serde_json::to_string(&update).unwrap(),
+///     r#"{"metadata":{"build":1234,"draft":null}}"#,
+/// );
+/// ```
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug, Default)]
+pub struct PasteUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Map<String, Value>>,
+}
+
+impl PasteUpdate {
+    /// Creates a new `PasteUpdate` which does not change anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the new content of the paste.
+    pub fn content(mut self, content: impl Into<String>) -> Self {
+        self.content = Some(content.into());
+        self
+    }
+
+    /// Merges all keys of the given metadata, which must serialize to a
+    /// JSON object, into the update.
+    pub fn metadata<M: Serialize + ?Sized>(mut self, metadata: &M) -> Result<Self> {
+        match serde_json::to_value(metadata)? {
+            Value::Object(map) => self.metadata.get_or_insert_with(Map::new).extend(map),
+            Value::Null => {}
+            _ => return Err(Error::InvalidMetadata),
+        }
+        Ok(self)
+    }
+
+    /// Sets the given metadata key to the given value serialized as JSON.
+    pub fn set_metadata_key<T: Serialize + ?Sized>(
+        mut self,
+        key: impl Into<String>,
+        value: &T,
+    ) -> Result<Self> {
+        let value = serde_json::to_value(value)?;
+        self.metadata
+            .get_or_insert_with(Map::new)
+            .insert(key.into(), value);
+        Ok(self)
+    }
+
+    /// Removes the given metadata key from the paste.
+    pub fn remove_metadata_key(mut self, key: impl Into<String>) -> Self {
+        self.metadata
+            .get_or_insert_with(Map::new)
+            .insert(key.into(), Value::Null);
+        self
+    }
+
+    /// Returns `true` if the update does not change anything.
+    pub fn is_empty(&self) -> bool {
+        self.content.is_none() && self.metadata.as_ref().is_none_or(Map::is_empty)
+    }
+}
+
+/// A secret value, like the value of a `ModificationToken` or an
+/// `AdminToken`.
+///
+/// The value is redacted in `Debug` and `Display` output, so that it does
+/// not accidentally end up in logs, and its memory is zeroed when dropped.
+/// The actual value is only accessible via `expose`.
+///
+/// # Example
+/// ```
+/// # use pasty_rs::model::SecretToken;
+/// let token = SecretToken::new("some-token");
+/// assert_eq!(format!("{token:?}"), "SecretToken(***)");
+/// assert_eq!(token.expose(), "some-token");
+/// ```
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct SecretToken(String);
+
+impl SecretToken {
+    /// Creates a new `SecretToken` from the given value.
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into())
+    }
+
+    /// Returns the actual value of the token.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SecretToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretToken(***)")
+    }
+}
+
+impl fmt::Display for SecretToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+impl Drop for SecretToken {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// Defines a newtype around a `SecretToken` for a distinct kind of token.
+///
+/// The defined types can be created from strings, but not from each
+/// other, so that one kind of token can not be passed where another kind
+/// is expected.
+macro_rules! secret_token {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+        #[serde(transparent)]
+        pub struct $name(SecretToken);
+
+        impl $name {
+            #[doc = concat!("Creates a new `", stringify!($name), "` from the given value.")]
+            pub fn new(token: impl Into<String>) -> Self {
+                Self(SecretToken::new(token))
+            }
+
+            /// Returns the actual value of the token.
+            pub fn expose(&self) -> &str {
+                self.0.expose()
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(token: String) -> Self {
+                Self::new(token)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(token: &str) -> Self {
+                Self::new(token)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    };
+}
+
+secret_token! {
+    /// The modification token of a single paste, which is returned when
+    /// the paste is created.
+    ///
+    /// Like a `SecretToken`, the token is redacted in `Debug` and `Display`
+    /// output and its memory is zeroed when dropped.
+    ///
+    /// # Example
+    /// ```
+    /// # use pasty_rs::model::ModificationToken;
+    /// let token = ModificationToken::new("some-token");
+    /// assert_eq!(format!("{token:?}"), "ModificationToken(SecretToken(***))");
+    /// assert_eq!(token.expose(), "some-token");
+    /// ```
+    ModificationToken
+}
+
+secret_token! {
+    /// The admin token of a pasty instance, which permits modifying and
+    /// deleting any paste.
+    ///
+    /// In contrast to a `ModificationToken`, an admin token is never
+    /// returned by the API and must be created explicitly, so that a
+    /// per-paste token can not be passed to `UnauthenticatedClient::admin`.
+    /// Like a `SecretToken`, the token is redacted in `Debug` and `Display`
+    /// output and its memory is zeroed when dropped.
+    ///
+    /// # Example
+    /// ```
+    /// # use pasty_rs::model::AdminToken;
+    /// let token = AdminToken::new("some-admin-token");
+    /// assert_eq!(format!("{token}"), "***");
+    /// ```
+    AdminToken
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct CreatedPaste<M = Metadata> {
     #[serde(rename = "modificationToken")]
-    pub modification_token: String,
+    pub modification_token: ModificationToken,
     #[serde(flatten)]
-    pub paste: Paste,
+    pub paste: Paste<M>,
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct ReportRequest {
+    pub reason: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct ReportResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct ErrorResponse {
+    pub message: String,
 }
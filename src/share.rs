@@ -0,0 +1,206 @@
+//! Frontend links of pastes.
+//!
+//! The pasty web frontend serves pastes at `https://host/{id}` with an
+//! optional language extension (`https://host/{id}.{ext}`) used for
+//! syntax highlighting. Encrypted pastes additionally carry their key in
+//! the URL fragment (`https://host/{id}.{ext}#{key}`).
+//!
+//! # Example
+//! ```
+//! # use pasty_rs::share::ShareUrl;
+//! let url: ShareUrl = "https://pasty.lus.pm/abcdef.rs#secret".parse().unwrap();
+//! assert_eq!(url.host().as_str(), "https://pasty.lus.pm/");
+//! assert_eq!(url.id(), "abcdef");
+//! assert_eq!(url.extension(), Some("rs"));
+//! assert_eq!(url.key(), Some("secret"));
+//!
+//! let url = ShareUrl::new(url.host().clone(), "ghijkl").with_extension("toml");
+//! assert_eq!(url.to_string(), "https://pasty.lus.pm/ghijkl.toml");
+//! ```
+
+use crate::{
+    errors::{Error, Result},
+    model::{CreatedPaste, Paste},
+};
+use percent_encoding::percent_decode_str;
+use std::{fmt, str::FromStr};
+use url::Url;
+
+/// A link to a paste in the pasty web frontend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareUrl {
+    host: Url,
+    id: String,
+    extension: Option<String>,
+    key: Option<String>,
+}
+
+impl ShareUrl {
+    /// Creates a new `ShareUrl` for the paste with the given ID on the
+    /// given host.
+    ///
+    /// If the host has a path, it is preserved and treated as the base
+    /// path of the frontend.
+    pub fn new(host: Url, id: impl Into<String>) -> Self {
+        Self {
+            host: base_url(host),
+            id: id.into(),
+            extension: None,
+            key: None,
+        }
+    }
+
+    /// Creates a new `ShareUrl` for the given paste on the given host,
+    /// regardless of the type of its metadata.
+    ///
+    /// # Example
+    /// ```
+    /// # use pasty_rs::{model::Paste, share::ShareUrl};
+    /// let paste = Paste {
+    ///     id: "abcdef".into(),
+    ///     content: "build log".into(),
+    ///     created: 1_700_000_000,
+    ///     metadata: Some(1234u64),
+    /// };
+    /// let url = ShareUrl::from_paste("https://pasty.lus.pm".parse().unwrap(), &paste);
+    /// assert_eq!(url.to_string(), "https://pasty.lus.pm/abcdef");
+    /// ```
+    pub fn from_paste<M>(host: Url, paste: &Paste<M>) -> Self {
+        Self::new(host, &paste.id)
+    }
+
+    /// Creates a new `ShareUrl` for the given created paste on the given
+    /// host, regardless of the type of its metadata.
+    pub fn from_created_paste<M>(host: Url, paste: &CreatedPaste<M>) -> Self {
+        Self::from_paste(host, &paste.paste)
+    }
+
+    /// Parses a frontend link into a `ShareUrl`.
+    ///
+    /// The last path segment is taken as paste ID and optional extension.
+    /// All previous path segments are kept as part of the host.
+    pub fn parse(url: &str) -> Result<Self> {
+        let mut url = Url::parse(url)?;
+
+        let segment = url
+            .path_segments()
+            .and_then(|mut s| s.next_back())
+            .filter(|s| !s.is_empty())
+            .map(|s| percent_decode_str(s).decode_utf8_lossy().into_owned())
+            .ok_or_else(|| Error::InvalidShareUrl("url does not contain a paste id".into()))?;
+
+        let (id, extension) = match segment.split_once('.') {
+            Some((id, ext)) => (
+                id.to_string(),
+                Some(ext.to_string()).filter(|e| !e.is_empty()),
+            ),
+            None => (segment, None),
+        };
+
+        let key = url
+            .fragment()
+            .filter(|f| !f.is_empty())
+            .map(ToString::to_string);
+
+        url.set_fragment(None);
+        url.set_query(None);
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop().push("");
+        }
+
+        Ok(Self {
+            host: url,
+            id,
+            extension,
+            key,
+        })
+    }
+
+    /// Sets the language extension used by the frontend for syntax
+    /// highlighting.
+    pub fn with_extension(mut self, extension: impl Into<String>) -> Self {
+        self.extension = Some(extension.into());
+        self
+    }
+
+    /// Sets the key used by the frontend to decrypt the paste.
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Returns the base URL of the frontend.
+    pub fn host(&self) -> &Url {
+        &self.host
+    }
+
+    /// Returns the ID of the paste.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the language extension, if set.
+    pub fn extension(&self) -> Option<&str> {
+        self.extension.as_deref()
+    }
+
+    /// Returns the encryption key, if set.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// Returns the encryption key parsed as `EncryptionKey`, if set.
+    #[cfg(feature = "encryption")]
+    pub fn encryption_key(&self) -> Option<Result<crate::encryption::EncryptionKey>> {
+        self.key.as_deref().map(str::parse)
+    }
+
+    /// Builds the frontend link.
+    pub fn to_url(&self) -> Url {
+        let mut segment = self.id.clone();
+        if let Some(extension) = &self.extension {
+            segment.push('.');
+            segment.push_str(extension);
+        }
+
+        let mut url = self.host.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop_if_empty().push(&segment);
+        }
+        url.set_fragment(self.key.as_deref());
+        url
+    }
+}
+
+impl fmt::Display for ShareUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.to_url().as_str())
+    }
+}
+
+impl FromStr for ShareUrl {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl From<ShareUrl> for Url {
+    fn from(value: ShareUrl) -> Self {
+        value.to_url()
+    }
+}
+
+/// Strips query and fragment from the given URL and ensures that its
+/// path ends with a slash.
+fn base_url(mut url: Url) -> Url {
+    url.set_fragment(None);
+    url.set_query(None);
+    if !url.path().ends_with('/') {
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.push("");
+        }
+    }
+    url
+}
@@ -0,0 +1,493 @@
+//! In-process fake pasty server for tests.
+//!
+//! This module is only available with the `testing` feature enabled.
+//!
+//! The `FakeServer` binds to a random port on localhost and implements
+//! the pasty API endpoints with an in-memory paste storage, including
+//! modification token checks. Failures and latency can be injected to
+//! test error handling of code using the API clients.
+//!
+//! # Example
+//! ```
+//! # use pasty_rs::testing::FakeServer;
+//! # #[tokio::main]
+//! # async fn main() {
+//! let server = FakeServer::start().await;
+//! let client = server.client();
+//!
+//! let created = client.create_paste("hello pasty!", None).await.unwrap();
+//! let paste = client.paste(&created.paste.id).await.unwrap();
+//! assert_eq!(paste.content, "hello pasty!");
+//!
+//! let client = client.authenticate("wrong-token");
+//! let err = client.delete_paste(&paste.id).await.unwrap_err();
+//! assert!(err.is_unauthorized());
+//! # }
+//! ```
+
+use crate::client::UnauthenticatedClient;
+use hyper::{
+    body::to_bytes,
+    header::{AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use percent_encoding::percent_decode_str;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{hash_map::RandomState, HashMap, VecDeque},
+    convert::Infallible,
+    hash::{BuildHasher, Hasher},
+    net::{SocketAddr, TcpListener},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::oneshot;
+use url::Url;
+
+/// A failure response returned by the `FakeServer` instead of handling
+/// the next request.
+#[derive(Clone, Debug)]
+pub struct Failure {
+    pub status: StatusCode,
+    pub message: String,
+    pub retry_after: Option<Duration>,
+}
+
+impl Failure {
+    /// Creates a failure responding with the given status code.
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            status,
+            message: status.canonical_reason().unwrap_or_default().to_string(),
+            retry_after: None,
+        }
+    }
+
+    /// Sets the message of the pasty JSON error response.
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Sets the `Retry-After` header of the failure response.
+    pub fn retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
+}
+
+impl From<StatusCode> for Failure {
+    fn from(value: StatusCode) -> Self {
+        Self::new(value)
+    }
+}
+
+/// A paste stored in the `FakeServer`.
+#[derive(Clone, Debug)]
+pub struct StoredPaste {
+    pub id: String,
+    pub content: String,
+    pub created: u64,
+    pub metadata: Option<Map<String, Value>>,
+    pub modification_token: String,
+}
+
+impl StoredPaste {
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "content": self.content,
+            "created": self.created,
+            "metadata": self.metadata,
+        })
+    }
+}
+
+/// A report received by the `FakeServer`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    pub paste_id: String,
+    pub reason: String,
+}
+
+struct State {
+    pastes: HashMap<String, StoredPaste>,
+    reports: Vec<Report>,
+    failures: VecDeque<Failure>,
+    latency: Option<Duration>,
+    reports_enabled: bool,
+    modification_tokens: bool,
+    paste_lifetime: isize,
+    admin_token: Option<String>,
+    requests: usize,
+    random: RandomState,
+    generated: usize,
+}
+
+/// An in-process fake pasty server listening on localhost.
+///
+/// The server is shut down when the `FakeServer` is dropped.
+pub struct FakeServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl FakeServer {
+    /// Starts a new fake server on a random port on localhost.
+    ///
+    /// # Panics
+    /// Panics if binding the listener fails or if not called within a
+    /// tokio runtime.
+    pub async fn start() -> Self {
+        let state = Arc::new(Mutex::new(State {
+            pastes: HashMap::new(),
+            reports: Vec::new(),
+            failures: VecDeque::new(),
+            latency: None,
+            reports_enabled: true,
+            modification_tokens: true,
+            paste_lifetime: -1,
+            admin_token: None,
+            requests: 0,
+            random: RandomState::new(),
+            generated: 0,
+        }));
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("binding fake server listener");
+        listener
+            .set_nonblocking(true)
+            .expect("setting fake server listener non-blocking");
+        let addr = listener.local_addr().expect("getting fake server address");
+
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_| {
+            let state = service_state.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |req| handle(state.clone(), req))) }
+        });
+
+        let (shutdown, rx) = oneshot::channel::<()>();
+        let server = Server::from_tcp(listener)
+            .expect("creating fake server")
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                rx.await.ok();
+            });
+        tokio::spawn(server);
+
+        Self {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+        }
+    }
+
+    /// Returns the base URL of the server.
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("http://{}/", self.addr)).expect("valid fake server url")
+    }
+
+    /// Returns a new `UnauthenticatedClient` for this server.
+    pub fn client(&self) -> UnauthenticatedClient {
+        UnauthenticatedClient::new(self.url()).expect("valid fake server url")
+    }
+
+    /// Makes the server respond with the given failure to the next
+    /// request instead of handling it. Failures queue up when called
+    /// multiple times.
+    pub fn fail_next(&self, failure: impl Into<Failure>) {
+        self.state().failures.push_back(failure.into());
+    }
+
+    /// Sets a delay applied before handling each request.
+    pub fn set_latency(&self, latency: Option<Duration>) {
+        self.state().latency = latency;
+    }
+
+    /// Sets whether reports are enabled on the server.
+    pub fn set_reports_enabled(&self, enabled: bool) {
+        self.state().reports_enabled = enabled;
+    }
+
+    /// Sets whether modification tokens are enabled on the server.
+    ///
+    /// If disabled, pastes may only be modified using the admin token.
+    pub fn set_modification_tokens(&self, enabled: bool) {
+        self.state().modification_tokens = enabled;
+    }
+
+    /// Sets the paste lifetime in milliseconds reported in the application
+    /// information, where `-1` means that pastes never expire.
+    pub fn set_paste_lifetime(&self, lifetime: isize) {
+        self.state().paste_lifetime = lifetime;
+    }
+
+    /// Sets the admin token which is accepted to modify and delete any
+    /// paste, or `None` to disable admin access.
+    pub fn set_admin_token(&self, token: Option<String>) {
+        self.state().admin_token = token;
+    }
+
+    /// Returns the stored paste with the given ID, if existent.
+    pub fn paste(&self, id: &str) -> Option<StoredPaste> {
+        self.state().pastes.get(id).cloned()
+    }
+
+    /// Returns all reports received by the server.
+    pub fn reports(&self) -> Vec<Report> {
+        self.state().reports.clone()
+    }
+
+    /// Returns the number of requests received by the server, including
+    /// requests answered with an injected failure.
+    pub fn request_count(&self) -> usize {
+        self.state().requests
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("fake server state poisoned")
+    }
+}
+
+impl Drop for FakeServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CreateBody {
+    content: String,
+    metadata: Option<Map<String, Value>>,
+}
+
+#[derive(Deserialize)]
+struct UpdateBody {
+    content: Option<String>,
+    metadata: Option<Map<String, Value>>,
+}
+
+#[derive(Deserialize)]
+struct ReportBody {
+    reason: String,
+}
+
+async fn handle(
+    state: Arc<Mutex<State>>,
+    req: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let latency = {
+        let mut state = state.lock().expect("fake server state poisoned");
+        state.requests += 1;
+        state.latency
+    };
+    if let Some(latency) = latency {
+        tokio::time::sleep(latency).await;
+    }
+
+    let failure = state
+        .lock()
+        .expect("fake server state poisoned")
+        .failures
+        .pop_front();
+    if let Some(failure) = failure {
+        let mut res = json_response(failure.status, json!({ "message": failure.message }));
+        if let Some(retry_after) = failure.retry_after {
+            res.headers_mut()
+                .insert(RETRY_AFTER, retry_after.as_secs().into());
+        }
+        return Ok(res);
+    }
+
+    let method = req.method().clone();
+    let segments: Vec<String> = req
+        .uri()
+        .path()
+        .trim_matches('/')
+        .split('/')
+        .map(|s| percent_decode_str(s).decode_utf8_lossy().into_owned())
+        .collect();
+    let token = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(ToString::to_string);
+    let body = to_bytes(req.into_body()).await.unwrap_or_default();
+
+    let mut state = state.lock().expect("fake server state poisoned");
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let (status, body) = match (method, segments.as_slice()) {
+        (Method::GET, ["api", "v2", "info"]) => state.info(),
+        (Method::GET, ["api", "v2", "pastes", id]) => state.get(id),
+        (Method::POST, ["api", "v2", "pastes"]) => state.create(&body),
+        (Method::PATCH, ["api", "v2", "pastes", id]) => state.update(id, token, &body),
+        (Method::DELETE, ["api", "v2", "pastes", id]) => state.delete(id, token),
+        (Method::POST, ["api", "v2", "pastes", id, "report"]) => state.report(id, &body),
+        _ => error(StatusCode::NOT_FOUND, "not found"),
+    };
+
+    Ok(json_response(status, body))
+}
+
+impl State {
+    fn info(&self) -> (StatusCode, Value) {
+        (
+            StatusCode::OK,
+            json!({
+                "modificationTokens": self.modification_tokens,
+                "pasteLifetime": self.paste_lifetime,
+                "reports": self.reports_enabled,
+                "version": "fake",
+            }),
+        )
+    }
+
+    fn get(&self, id: &str) -> (StatusCode, Value) {
+        match self.pastes.get(id) {
+            Some(paste) => (StatusCode::OK, paste.to_json()),
+            None => error(StatusCode::NOT_FOUND, "paste not found"),
+        }
+    }
+
+    fn create(&mut self, body: &[u8]) -> (StatusCode, Value) {
+        let Ok(body) = serde_json::from_slice::<CreateBody>(body) else {
+            return error(StatusCode::BAD_REQUEST, "invalid request body");
+        };
+
+        let paste = StoredPaste {
+            id: self.random_string(8),
+            content: body.content,
+            created: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            metadata: body.metadata,
+            modification_token: self.random_string(32),
+        };
+
+        let mut res = paste.to_json();
+        res["modificationToken"] = paste.modification_token.clone().into();
+        self.pastes.insert(paste.id.clone(), paste);
+
+        (StatusCode::CREATED, res)
+    }
+
+    fn update(&mut self, id: &str, token: Option<String>, body: &[u8]) -> (StatusCode, Value) {
+        let Ok(body) = serde_json::from_slice::<UpdateBody>(body) else {
+            return error(StatusCode::BAD_REQUEST, "invalid request body");
+        };
+
+        let paste = match self.authorize(id, token) {
+            Ok(paste) => paste,
+            Err(err) => return err,
+        };
+
+        if let Some(content) = body.content {
+            paste.content = content;
+        }
+        if let Some(metadata) = body.metadata {
+            let current = paste.metadata.get_or_insert_with(Map::new);
+            for (key, value) in metadata {
+                if value.is_null() {
+                    current.remove(&key);
+                } else {
+                    current.insert(key, value);
+                }
+            }
+        }
+
+        (StatusCode::OK, paste.to_json())
+    }
+
+    fn delete(&mut self, id: &str, token: Option<String>) -> (StatusCode, Value) {
+        if let Err(err) = self.authorize(id, token) {
+            return err;
+        }
+
+        self.pastes.remove(id);
+        (StatusCode::OK, Value::Null)
+    }
+
+    fn report(&mut self, id: &str, body: &[u8]) -> (StatusCode, Value) {
+        if !self.reports_enabled {
+            return error(StatusCode::NOT_FOUND, "not found");
+        }
+
+        let Ok(body) = serde_json::from_slice::<ReportBody>(body) else {
+            return error(StatusCode::BAD_REQUEST, "invalid request body");
+        };
+
+        if !self.pastes.contains_key(id) {
+            return error(StatusCode::NOT_FOUND, "paste not found");
+        }
+
+        self.reports.push(Report {
+            paste_id: id.to_string(),
+            reason: body.reason,
+        });
+
+        (
+            StatusCode::OK,
+            json!({ "success": true, "message": "the paste has been reported" }),
+        )
+    }
+
+    fn authorize(
+        &mut self,
+        id: &str,
+        token: Option<String>,
+    ) -> Result<&mut StoredPaste, (StatusCode, Value)> {
+        let paste = self
+            .pastes
+            .get_mut(id)
+            .ok_or_else(|| error(StatusCode::NOT_FOUND, "paste not found"))?;
+
+        let is_admin = token.is_some() && token == self.admin_token;
+        let is_owner =
+            self.modification_tokens && token.as_deref() == Some(paste.modification_token.as_str());
+        if !is_admin && !is_owner {
+            return Err(error(StatusCode::UNAUTHORIZED, "unauthorized"));
+        }
+
+        Ok(paste)
+    }
+
+    fn random_string(&mut self, len: usize) -> String {
+        const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+        self.generated += 1;
+        let mut hasher = self.random.build_hasher();
+        hasher.write_usize(self.generated);
+        (0..len)
+            .map(|i| {
+                hasher.write_usize(i);
+                CHARS[hasher.finish() as usize % CHARS.len()] as char
+            })
+            .collect()
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> (StatusCode, Value) {
+    (status, json!({ "message": message }))
+}
+
+fn json_response(status: StatusCode, body: Value) -> Response<Body> {
+    let body = if body.is_null() {
+        Body::empty()
+    } else {
+        Body::from(body.to_string())
+    };
+
+    let mut res = Response::new(body);
+    *res.status_mut() = status;
+    res.headers_mut().insert(
+        CONTENT_TYPE,
+        "application/json".parse().expect("valid header"),
+    );
+    res
+}
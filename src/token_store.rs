@@ -0,0 +1,219 @@
+//! Persistent storage of paste modification tokens.
+//!
+//! The modification token of a paste is only returned once when the
+//! paste is created. When a `TokenStore` is attached to a client via
+//! `UnauthenticatedClient::with_token_store`, the tokens of all pastes
+//! created with that client are recorded automatically, so that an
+//! `AuthenticatedClient` can later be obtained by just the paste ID via
+//! `UnauthenticatedClient::authenticate_paste`.
+//!
+//! # Example
+//! ```no_run
+//! # use pasty_rs::{client::*, token_store::FileTokenStore};
+//! # #[tokio::main]
+//! # async fn main() {
+//! let store = FileTokenStore::new(FileTokenStore::default_path().unwrap());
+//! let client = UnauthenticatedClient::new("https://pasty.lus.pm")
+//!     .unwrap()
+//!     .with_token_store(store);
+//!
+//! let paste = client.create_paste("hello pasty!", None).await.unwrap();
+//!
+//! // Later, possibly in another process:
+//! let auth_client = client.authenticate_paste(&paste.paste.id).unwrap();
+//! auth_client.delete_paste(&paste.paste.id).await.unwrap();
+//! # }
+//! ```
+
+use crate::errors::Result;
+use std::{
+    collections::BTreeMap,
+    env,
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use url::Url;
+
+/// A storage of modification tokens keyed by pasty host and paste ID.
+pub trait TokenStore: Send + Sync {
+    /// Returns the stored token of the paste with the given ID on the
+    /// given host, if existent.
+    fn get(&self, host: &Url, id: &str) -> Result<Option<String>>;
+
+    /// Stores the token of the paste with the given ID on the given host.
+    fn set(&self, host: &Url, id: &str, token: &str) -> Result<()>;
+
+    /// Removes the token of the paste with the given ID on the given
+    /// host, if existent.
+    fn remove(&self, host: &Url, id: &str) -> Result<()>;
+}
+
+impl<T: TokenStore + ?Sized> TokenStore for Arc<T> {
+    fn get(&self, host: &Url, id: &str) -> Result<Option<String>> {
+        (**self).get(host, id)
+    }
+
+    fn set(&self, host: &Url, id: &str, token: &str) -> Result<()> {
+        (**self).set(host, id, token)
+    }
+
+    fn remove(&self, host: &Url, id: &str) -> Result<()> {
+        (**self).remove(host, id)
+    }
+}
+
+type Tokens = BTreeMap<String, BTreeMap<String, String>>;
+
+/// A `TokenStore` persisting tokens in a JSON file.
+///
+/// The file is read on every access, so multiple processes can share
+/// the same file. Modifications hold an advisory lock on a `.lock` file
+/// next to the token file, so that concurrent modifications are not
+/// lost, and replace the file atomically. On unix systems, the file is
+/// only readable by the current user.
+pub struct FileTokenStore {
+    path: PathBuf,
+}
+
+impl FileTokenStore {
+    /// Creates a new `FileTokenStore` using the file at the given path.
+    /// The file and its parent directories are created on the first
+    /// write, if not existent.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the default location of the token file, which is
+    /// `$XDG_DATA_HOME/pasty-rs/tokens.json`, falling back to
+    /// `$HOME/.local/share/pasty-rs/tokens.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        let data_dir = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))?;
+        Some(data_dir.join("pasty-rs").join("tokens.json"))
+    }
+
+    /// Returns the path of the token file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self) -> Result<Tokens> {
+        match fs::read(&self.path) {
+            Ok(data) => Ok(serde_json::from_slice(&data)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Tokens::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn modify(&self, f: impl FnOnce(&mut Tokens)) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // The lock is released when the file is closed.
+        let lock = File::options()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(sibling_path(&self.path, ".lock"))?;
+        lock.lock()?;
+
+        let mut tokens = self.read()?;
+        f(&mut tokens);
+        write_atomic(&self.path, &serde_json::to_vec_pretty(&tokens)?)?;
+
+        Ok(())
+    }
+}
+
+impl TokenStore for FileTokenStore {
+    fn get(&self, host: &Url, id: &str) -> Result<Option<String>> {
+        Ok(self
+            .read()?
+            .get(&host_key(host))
+            .and_then(|pastes| pastes.get(id))
+            .cloned())
+    }
+
+    fn set(&self, host: &Url, id: &str, token: &str) -> Result<()> {
+        self.modify(|tokens| {
+            tokens
+                .entry(host_key(host))
+                .or_default()
+                .insert(id.to_string(), token.to_string());
+        })
+    }
+
+    fn remove(&self, host: &Url, id: &str) -> Result<()> {
+        self.modify(|tokens| {
+            let key = host_key(host);
+            if let Some(pastes) = tokens.get_mut(&key) {
+                pastes.remove(id);
+                if pastes.is_empty() {
+                    tokens.remove(&key);
+                }
+            }
+        })
+    }
+}
+
+/// Returns the key of the given host in the token file, which is the
+/// host URL without query, fragment and trailing slash.
+pub(crate) fn host_key(host: &Url) -> String {
+    let mut host = host.clone();
+    host.set_query(None);
+    host.set_fragment(None);
+    host.as_str().trim_end_matches('/').to_string()
+}
+
+/// Atomically replaces the file at the given path with the given data.
+///
+/// The data is written to a temporary file next to the target first,
+/// which is named uniquely per process and write, so that concurrent
+/// writers never share a temporary file. On unix systems, the file is
+/// created only readable by the current user.
+pub(crate) fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    static WRITES: AtomicU64 = AtomicU64::new(0);
+    let tmp = sibling_path(
+        path,
+        &format!(
+            ".{}.{}.tmp",
+            process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ),
+    );
+
+    let mut options = File::options();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let res = options
+        .open(&tmp)
+        .and_then(|mut file| file.write_all(data))
+        .and_then(|_| fs::rename(&tmp, path));
+    if res.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    res
+}
+
+/// Returns the path of the given path with the given suffix appended to
+/// its file name.
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(suffix);
+    path.with_file_name(name)
+}
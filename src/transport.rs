@@ -0,0 +1,289 @@
+//! HTTP transports used by the API clients to perform requests.
+//!
+//! The clients are generic over a `Transport`, which executes plain
+//! `HttpRequest`s and returns `HttpResponse`s. By default, the
+//! `ReqwestTransport` is used, which is only available with the default
+//! `reqwest` feature enabled. Other HTTP stacks can be used by
+//! implementing `Transport` for them and passing them to
+//! `UnauthenticatedClient::with_transport`.
+//!
+//! # Example
+//! ```
+//! # use pasty_rs::{client::*, errors::Result, transport::*};
+//! # use std::future::Future;
+//! /// A transport answering every request from memory.
+//! struct StaticTransport;
+//!
+//! impl Transport for StaticTransport {
+//!     fn execute(&self, _: HttpRequest) -> impl Future<Output = Result<HttpResponse>> + Send {
+//!         let body = r#"{"modificationTokens":true,"pasteLifetime":-1,"reports":false,"version":"static"}"#;
+//!         std::future::ready(Ok(HttpResponse::new(StatusCode::OK, body)))
+//!     }
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let client = UnauthenticatedClient::with_transport("https://pasty.lus.pm", StaticTransport).unwrap();
+//! let info = client.application_information().await.unwrap();
+//! assert_eq!(info.version, "static");
+//! # }
+//! ```
+
+use crate::errors::Result;
+use serde::Serialize;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+    time::Duration,
+};
+use url::Url;
+
+pub use http::{
+    header::{self, HeaderMap, HeaderValue},
+    Method, StatusCode,
+};
+
+/// A plain HTTP request performed by a `Transport`.
+#[derive(Clone, Debug)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+}
+
+impl HttpRequest {
+    /// Creates a new request with the given method and URL without any
+    /// headers or body.
+    pub fn new(method: Method, url: Url) -> Self {
+        Self {
+            method,
+            url,
+            headers: HeaderMap::new(),
+            body: None,
+        }
+    }
+
+    /// Sets the given value serialized as JSON as body of the request.
+    pub fn json<T: Serialize + ?Sized>(mut self, body: &T) -> Result<Self> {
+        self.body = Some(serde_json::to_vec(body)?);
+        self.headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        Ok(self)
+    }
+
+    /// Sets the given token as bearer token in the `Authorization` header
+    /// of the request.
+    pub fn bearer_auth(mut self, token: &str) -> Result<Self> {
+        let mut value = HeaderValue::try_from(format!("Bearer {token}"))?;
+        value.set_sensitive(true);
+        self.headers.insert(header::AUTHORIZATION, value);
+        Ok(self)
+    }
+}
+
+/// A plain HTTP response returned by a `Transport`.
+#[derive(Clone, Debug)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Creates a new response with the given status and body without
+    /// any headers.
+    pub fn new(status: StatusCode, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+}
+
+/// An HTTP backend executing the requests of the async API clients.
+///
+/// Errors of the underlying HTTP stack should be returned as
+/// `Error::Transport`. Connection errors and timeouts should be marked
+/// as retryable, so that they are retried according to the retry policy
+/// of the client.
+pub trait Transport: Send + Sync {
+    /// Executes the given request and returns the response regardless of
+    /// its status code.
+    fn execute(&self, request: HttpRequest) -> impl Future<Output = Result<HttpResponse>> + Send;
+
+    /// Waits for the given duration before a failed request is retried.
+    ///
+    /// The default implementation does not depend on any async runtime
+    /// and waits on a separate thread. Transports bound to a runtime
+    /// should use the timer of that runtime instead.
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send {
+        ThreadSleep {
+            duration,
+            state: None,
+        }
+    }
+}
+
+/// A runtime agnostic timer future which completes after the given
+/// duration has passed on a separate thread.
+struct ThreadSleep {
+    duration: Duration,
+    state: Option<Arc<Mutex<SleepState>>>,
+}
+
+#[derive(Default)]
+struct SleepState {
+    done: bool,
+    waker: Option<Waker>,
+}
+
+impl Future for ThreadSleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.duration.is_zero() {
+            return Poll::Ready(());
+        }
+
+        let duration = self.duration;
+        let state = self.state.get_or_insert_with(|| {
+            let state = Arc::new(Mutex::new(SleepState::default()));
+            let timer = state.clone();
+            thread::spawn(move || {
+                thread::sleep(duration);
+                let mut timer = timer.lock().unwrap_or_else(|e| e.into_inner());
+                timer.done = true;
+                if let Some(waker) = timer.waker.take() {
+                    waker.wake();
+                }
+            });
+            state
+        });
+
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.done {
+            return Poll::Ready(());
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// The default `Transport` backed by a `reqwest::Client`.
+///
+/// This type is only available with the `reqwest` feature enabled.
+#[cfg(feature = "reqwest")]
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestTransport {
+    /// Creates a new transport using the given `reqwest::Client`.
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl From<reqwest::Client> for ReqwestTransport {
+    fn from(value: reqwest::Client) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Transport for ReqwestTransport {
+    async fn execute(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let mut req = self
+            .client
+            .request(request.method, request.url)
+            .headers(request.headers);
+        if let Some(body) = request.body {
+            req = req.body(body);
+        }
+
+        let res = req.send().await?;
+        Ok(HttpResponse {
+            status: res.status(),
+            headers: res.headers().clone(),
+            body: res.bytes().await?.into(),
+        })
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send {
+        tokio::time::sleep(duration)
+    }
+}
+
+/// An HTTP backend executing the requests of the blocking API clients.
+///
+/// This trait is only available with the `blocking` feature enabled.
+#[cfg(feature = "blocking")]
+pub trait BlockingTransport: Send + Sync {
+    /// Executes the given request and returns the response regardless of
+    /// its status code.
+    fn execute(&self, request: HttpRequest) -> Result<HttpResponse>;
+}
+
+/// The default `BlockingTransport` backed by a
+/// `reqwest::blocking::Client`.
+///
+/// This type is only available with the `blocking` and `reqwest`
+/// features enabled.
+#[cfg(all(feature = "blocking", feature = "reqwest"))]
+#[derive(Clone, Debug)]
+pub struct ReqwestBlockingTransport {
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(all(feature = "blocking", feature = "reqwest"))]
+impl ReqwestBlockingTransport {
+    /// Creates a new transport using the given
+    /// `reqwest::blocking::Client`.
+    pub fn new(client: reqwest::blocking::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(all(feature = "blocking", feature = "reqwest"))]
+impl Default for ReqwestBlockingTransport {
+    fn default() -> Self {
+        Self::new(reqwest::blocking::Client::new())
+    }
+}
+
+#[cfg(all(feature = "blocking", feature = "reqwest"))]
+impl From<reqwest::blocking::Client> for ReqwestBlockingTransport {
+    fn from(value: reqwest::blocking::Client) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(all(feature = "blocking", feature = "reqwest"))]
+impl BlockingTransport for ReqwestBlockingTransport {
+    fn execute(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let mut req = self
+            .client
+            .request(request.method, request.url)
+            .headers(request.headers);
+        if let Some(body) = request.body {
+            req = req.body(body);
+        }
+
+        let res = req.send()?;
+        Ok(HttpResponse {
+            status: res.status(),
+            headers: res.headers().clone(),
+            body: res.bytes()?.into(),
+        })
+    }
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "testing")]
+
+use pasty_rs::{model::AdminToken, testing::FakeServer};
+
+#[tokio::test]
+async fn admin_deletes_any_paste() {
+    let server = FakeServer::start().await;
+    server.set_admin_token(Some("admin-token".into()));
+
+    let client = server.client();
+    let first = client.create_paste("first", None).await.unwrap();
+    let second = client.create_paste("second", None).await.unwrap();
+
+    let admin = client.admin(AdminToken::new("admin-token"));
+    let results = admin
+        .delete_pastes([first.paste.id.as_str(), second.paste.id.as_str()])
+        .await;
+    assert!(results.iter().all(|(_, res)| res.is_ok()));
+    assert!(server.paste(&first.paste.id).is_none());
+    assert!(server.paste(&second.paste.id).is_none());
+}
+
+#[tokio::test]
+async fn admin_with_wrong_token_is_unauthorized() {
+    let server = FakeServer::start().await;
+    server.set_admin_token(Some("admin-token".into()));
+
+    let client = server.client();
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+
+    let admin = client.admin(AdminToken::new(created.modification_token.expose()));
+    server.set_modification_tokens(false);
+    let err = admin.delete_paste(&created.paste.id).await.unwrap_err();
+    assert!(err.is_unauthorized());
+    assert!(server.paste(&created.paste.id).is_some());
+}
@@ -0,0 +1,119 @@
+#![cfg(all(feature = "testing", feature = "blocking"))]
+
+use pasty_rs::{
+    client::{blocking::UnauthenticatedClient, PasteCache},
+    errors::{Error, Result},
+    model::AdminToken,
+    testing::FakeServer,
+    token_store::TokenStore,
+};
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+use url::Url;
+
+/// An in-memory `TokenStore` ignoring the host.
+#[derive(Default)]
+struct MemoryTokenStore(Mutex<HashMap<String, String>>);
+
+impl TokenStore for MemoryTokenStore {
+    fn get(&self, _: &Url, id: &str) -> Result<Option<String>> {
+        Ok(self.0.lock().unwrap().get(id).cloned())
+    }
+
+    fn set(&self, _: &Url, id: &str, token: &str) -> Result<()> {
+        self.0.lock().unwrap().insert(id.into(), token.into());
+        Ok(())
+    }
+
+    fn remove(&self, _: &Url, id: &str) -> Result<()> {
+        self.0.lock().unwrap().remove(id);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn blocking_client_round_trip() {
+    let server = FakeServer::start().await;
+    let url = server.url();
+
+    // The blocking client must not be used on the async runtime.
+    let (id, cached) = tokio::task::spawn_blocking(move || {
+        let client = UnauthenticatedClient::new(url)
+            .unwrap()
+            .with_token_store(MemoryTokenStore::default())
+            .with_cache(PasteCache::new(10, Duration::from_secs(60)));
+
+        let created = client.create_paste("hello pasty!", None).unwrap();
+        let id = created.paste.id.clone();
+        assert_eq!(client.paste(&id).unwrap().content, "hello pasty!");
+        assert_eq!(client.paste(&id).unwrap().content, "hello pasty!");
+        let cached = client.cache().unwrap().len();
+
+        let auth_client = client.authenticate_paste(&id).unwrap();
+        assert_eq!(
+            auth_client.token().expose(),
+            created.modification_token.expose()
+        );
+        auth_client.update_paste(&id, "new content", None).unwrap();
+        assert_eq!(client.paste(&id).unwrap().content, "new content");
+
+        auth_client.delete_paste(&id).unwrap();
+        assert!(client.cache().unwrap().is_empty());
+        assert!(matches!(
+            client.authenticate_paste(&id),
+            Err(Error::MissingToken(_))
+        ));
+        assert!(client.paste(&id).unwrap_err().is_not_found());
+
+        (id, cached)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(cached, 1);
+    assert!(server.paste(&id).is_none());
+    // The creation, the capabilities, the update, the deletion and three
+    // uncached requests of the paste.
+    assert_eq!(server.request_count(), 7);
+}
+
+#[tokio::test]
+async fn blocking_reports_are_checked() {
+    let server = FakeServer::start().await;
+    server.set_reports_enabled(false);
+    let url = server.url();
+
+    tokio::task::spawn_blocking(move || {
+        let client = UnauthenticatedClient::new(url).unwrap();
+        let created = client.create_paste("hello pasty!", None).unwrap();
+        let err = client.report_paste(&created.paste.id, "spam").unwrap_err();
+        assert!(err.is_unsupported());
+    })
+    .await
+    .unwrap();
+
+    assert!(server.reports().is_empty());
+}
+
+#[tokio::test]
+async fn blocking_admin_deletes_any_paste() {
+    let server = FakeServer::start().await;
+    server.set_admin_token(Some("admin-token".into()));
+    let url = server.url();
+
+    let ids = tokio::task::spawn_blocking(move || {
+        let client = UnauthenticatedClient::new(url).unwrap();
+        let ids: Vec<_> = ["first", "second"]
+            .into_iter()
+            .map(|content| client.create_paste(content, None).unwrap().paste.id)
+            .collect();
+
+        let admin = client.admin(AdminToken::new("admin-token"));
+        let results = admin.delete_pastes(ids.clone());
+        assert!(results.iter().all(|(_, res)| res.is_ok()));
+        ids
+    })
+    .await
+    .unwrap();
+
+    assert!(ids.iter().all(|id| server.paste(id).is_none()));
+}
@@ -0,0 +1,172 @@
+#![cfg(feature = "testing")]
+
+use pasty_rs::{
+    client::{PasteCache, UnauthenticatedClient},
+    errors::Result,
+    testing::FakeServer,
+    transport::{HttpRequest, HttpResponse, ReqwestTransport, StatusCode, Transport},
+};
+use std::time::Duration;
+
+#[tokio::test]
+async fn serves_cached_pastes() {
+    let server = FakeServer::start().await;
+    let client = server
+        .client()
+        .with_cache(PasteCache::new(10, Duration::from_secs(60)));
+
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+    let id = &created.paste.id;
+
+    for _ in 0..3 {
+        assert_eq!(client.paste(id).await.unwrap().content, "hello pasty!");
+    }
+    assert_eq!(server.request_count(), 3);
+    assert_eq!(client.cache().unwrap().len(), 1);
+}
+
+/// A transport failing all requests of the application information.
+struct WithoutInfo(ReqwestTransport);
+
+impl Transport for WithoutInfo {
+    async fn execute(&self, request: HttpRequest) -> Result<HttpResponse> {
+        if request.url.path().ends_with("/info") {
+            return Ok(HttpResponse::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                Vec::new(),
+            ));
+        }
+        self.0.execute(request).await
+    }
+}
+
+#[tokio::test]
+async fn caching_does_not_depend_on_capabilities() {
+    let server = FakeServer::start().await;
+    let client =
+        UnauthenticatedClient::with_transport(server.url(), WithoutInfo(Default::default()))
+            .unwrap()
+            .with_cache(PasteCache::new(10, Duration::from_secs(60)));
+
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+    client.paste(&created.paste.id).await.unwrap();
+    client.paste(&created.paste.id).await.unwrap();
+    assert_eq!(server.request_count(), 2);
+}
+
+#[tokio::test]
+async fn requests_capabilities_once() {
+    let server = FakeServer::start().await;
+    let client = server
+        .client()
+        .with_cache(PasteCache::new(10, Duration::from_secs(60)));
+
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+    client.paste(&created.paste.id).await.unwrap();
+
+    // The paste and the capabilities have been requested.
+    assert_eq!(server.request_count(), 3);
+
+    client.cache().unwrap().clear();
+    client.paste(&created.paste.id).await.unwrap();
+    assert_eq!(server.request_count(), 4);
+}
+
+#[tokio::test]
+async fn caches_with_unlimited_ttl() {
+    let server = FakeServer::start().await;
+    let client = server
+        .client()
+        .with_cache(PasteCache::new(10, Duration::MAX));
+
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+    client.paste(&created.paste.id).await.unwrap();
+    client.paste(&created.paste.id).await.unwrap();
+    assert_eq!(server.request_count(), 3);
+}
+
+#[tokio::test]
+async fn evicts_least_recently_used() {
+    let server = FakeServer::start().await;
+    let client = server
+        .client()
+        .with_cache(PasteCache::new(2, Duration::from_secs(60)));
+
+    let mut ids = Vec::new();
+    for content in ["a", "b", "c"] {
+        ids.push(client.create_paste(content, None).await.unwrap().paste.id);
+    }
+
+    client.paste(&ids[0]).await.unwrap();
+    client.paste(&ids[1]).await.unwrap();
+    client.paste(&ids[0]).await.unwrap();
+    assert_eq!(server.request_count(), 6);
+
+    // Evicts b, which has been used less recently than a.
+    client.paste(&ids[2]).await.unwrap();
+    assert_eq!(client.cache().unwrap().len(), 2);
+    client.paste(&ids[0]).await.unwrap();
+    assert_eq!(server.request_count(), 7);
+    client.paste(&ids[1]).await.unwrap();
+    assert_eq!(server.request_count(), 8);
+}
+
+#[tokio::test]
+async fn expires_after_ttl() {
+    let server = FakeServer::start().await;
+    let client = server
+        .client()
+        .with_cache(PasteCache::new(10, Duration::from_millis(100)));
+
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+    client.paste(&created.paste.id).await.unwrap();
+    client.paste(&created.paste.id).await.unwrap();
+    assert_eq!(server.request_count(), 3);
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    client.paste(&created.paste.id).await.unwrap();
+    assert_eq!(server.request_count(), 4);
+}
+
+#[tokio::test]
+async fn ttl_is_capped_by_paste_lifetime() {
+    let server = FakeServer::start().await;
+    server.set_paste_lifetime(2000);
+    let client = server
+        .client()
+        .with_cache(PasteCache::new(10, Duration::from_secs(60)));
+
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+    client.paste(&created.paste.id).await.unwrap();
+    client.paste(&created.paste.id).await.unwrap();
+    assert_eq!(server.request_count(), 3);
+
+    // The paste expires within two seconds after its creation, which
+    // is truncated to seconds.
+    tokio::time::sleep(Duration::from_millis(2100)).await;
+    let _ = client.paste(&created.paste.id).await;
+    assert_eq!(server.request_count(), 4);
+}
+
+#[tokio::test]
+async fn invalidates_updated_and_deleted_pastes() {
+    let server = FakeServer::start().await;
+    let client = server
+        .client()
+        .with_cache(PasteCache::new(10, Duration::from_secs(60)));
+
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+    let id = created.paste.id.clone();
+    client.paste(&id).await.unwrap();
+
+    let auth_client = client.clone().authenticate(created.modification_token);
+    auth_client
+        .update_paste(&id, "new content", None)
+        .await
+        .unwrap();
+    assert_eq!(client.paste(&id).await.unwrap().content, "new content");
+
+    auth_client.delete_paste(&id).await.unwrap();
+    assert!(client.cache().unwrap().is_empty());
+    assert!(client.paste(&id).await.unwrap_err().is_not_found());
+}
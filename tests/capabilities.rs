@@ -0,0 +1,66 @@
+#![cfg(feature = "testing")]
+
+use pasty_rs::testing::FakeServer;
+
+#[tokio::test]
+async fn delete_does_not_request_capabilities() {
+    let server = FakeServer::start().await;
+    let client = server.client();
+
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+    let id = created.paste.id.clone();
+    client
+        .authenticate(created.modification_token)
+        .delete_paste(&id)
+        .await
+        .unwrap();
+
+    assert!(server.paste(&id).is_none());
+    assert_eq!(server.request_count(), 2);
+}
+
+#[tokio::test]
+async fn reports_are_always_checked() {
+    let server = FakeServer::start().await;
+    server.set_reports_enabled(false);
+    let client = server.client();
+
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+    let id = created.paste.id.clone();
+
+    let err = client.report_paste(&id, "spam").await.unwrap_err();
+    assert!(err.is_unsupported());
+    assert!(server.reports().is_empty());
+}
+
+#[tokio::test]
+async fn checks_fail_early_when_unsupported() {
+    let server = FakeServer::start().await;
+    server.set_reports_enabled(false);
+    let client = server.client().with_capability_checks();
+
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+    let id = created.paste.id.clone();
+
+    let err = client.report_paste(&id, "spam").await.unwrap_err();
+    assert!(err.is_unsupported());
+    assert!(server.reports().is_empty());
+
+    // Only the paste creation and the capabilities have been requested.
+    assert_eq!(server.request_count(), 2);
+}
+
+#[tokio::test]
+async fn checks_reject_modification_tokens() {
+    let server = FakeServer::start().await;
+    server.set_modification_tokens(false);
+    let client = server.client().with_capability_checks();
+
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+    let id = created.paste.id.clone();
+    let client = client.authenticate(created.modification_token);
+
+    let err = client.delete_paste(&id).await.unwrap_err();
+    assert!(err.is_unsupported());
+    assert!(server.paste(&id).is_some());
+}
@@ -0,0 +1,119 @@
+use pasty_rs::{disk_cache::DiskCache, model::Paste};
+use std::{
+    env, fs,
+    path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Duration,
+};
+use url::Url;
+
+fn temp_dir() -> PathBuf {
+    static DIRS: AtomicUsize = AtomicUsize::new(0);
+    env::temp_dir().join(format!(
+        "pasty-rs-disk-cache-{}-{}",
+        process::id(),
+        DIRS.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+fn host() -> Url {
+    Url::parse("https://pasty.lus.pm/").unwrap()
+}
+
+fn paste(id: &str) -> Paste {
+    Paste {
+        id: id.into(),
+        content: "x".repeat(100),
+        created: 1_700_000_000,
+        metadata: None,
+    }
+}
+
+fn entry_size(cache: &DiskCache) -> u64 {
+    fs::read_dir(cache.dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().metadata().unwrap().len())
+        .max()
+        .unwrap()
+}
+
+/// Waits, so that subsequent writes get distinct modification times.
+fn tick() {
+    thread::sleep(Duration::from_millis(20));
+}
+
+#[test]
+fn get_returns_stale_paste() {
+    let cache = DiskCache::new(temp_dir());
+    assert_eq!(cache.get(&host(), "aaaaaa").unwrap(), None);
+
+    cache.set(&host(), &paste("aaaaaa")).unwrap();
+    let cached = cache.get(&host(), "aaaaaa").unwrap().unwrap();
+    assert!(cached.stale);
+    assert_eq!(cached.paste, paste("aaaaaa"));
+
+    let other = Url::parse("https://other.example/").unwrap();
+    assert_eq!(cache.get(&other, "aaaaaa").unwrap(), None);
+
+    cache.remove(&host(), "aaaaaa").unwrap();
+    assert_eq!(cache.get(&host(), "aaaaaa").unwrap(), None);
+
+    fs::remove_dir_all(cache.dir()).unwrap();
+}
+
+#[test]
+fn evicts_least_recently_used_when_full() {
+    let dir = temp_dir();
+    let cache = DiskCache::new(&dir);
+    cache.set(&host(), &paste("aaaaaa")).unwrap();
+    let size = entry_size(&cache);
+
+    let cache = DiskCache::new(&dir).with_max_size(size * 2 + size / 2);
+    tick();
+    cache.set(&host(), &paste("bbbbbb")).unwrap();
+    tick();
+    // Reading marks the paste as recently used.
+    cache.get(&host(), "aaaaaa").unwrap().unwrap();
+    tick();
+    cache.set(&host(), &paste("cccccc")).unwrap();
+
+    assert!(cache.get(&host(), "aaaaaa").unwrap().is_some());
+    assert!(cache.get(&host(), "bbbbbb").unwrap().is_none());
+    assert!(cache.get(&host(), "cccccc").unwrap().is_some());
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn falls_back_to_stale_paste_when_unreachable() {
+    use pasty_rs::{testing::FakeServer, transport::StatusCode};
+
+    let server = FakeServer::start().await;
+    let client = server.client().with_disk_cache(DiskCache::new(temp_dir()));
+
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+    let id = &created.paste.id;
+
+    let fetched = client.paste_with_fallback(id).await.unwrap();
+    assert!(!fetched.stale);
+
+    server.fail_next(StatusCode::SERVICE_UNAVAILABLE);
+    let cached = client.paste_with_fallback(id).await.unwrap();
+    assert!(cached.stale);
+    assert_eq!(cached.paste, fetched.paste);
+
+    server.fail_next(StatusCode::NOT_FOUND);
+    assert!(client
+        .paste_with_fallback(id)
+        .await
+        .unwrap_err()
+        .is_not_found());
+    server.fail_next(StatusCode::SERVICE_UNAVAILABLE);
+    assert!(client.paste_with_fallback(id).await.is_err());
+
+    fs::remove_dir_all(client.disk_cache().unwrap().dir()).unwrap();
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "encryption")]
+
+use pasty_rs::{
+    encryption::EncryptionKey,
+    model::{Metadata, Paste, PfEncryption},
+    share::ShareUrl,
+};
+
+// Produced with WebCrypto (`crypto.subtle.encrypt` with `AES-GCM`), the API
+// the pasty web frontend uses to encrypt pastes, from a fixed key and IV.
+const KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8";
+const IV: &str = "oKGio6Slpqeoqaqr";
+const CONTENT: &str =
+    "jn0QQSrrZM0NCKenbx/grhHfLWmywCcOvGhU6RHfEG+2V2cPMITT8OMd7LoQJPwP3PBxT2ADwg==";
+const PLAINTEXT: &str = "hello from the pasty web frontend! 🦀";
+
+fn paste() -> Paste {
+    Paste {
+        id: "abcdef".into(),
+        content: CONTENT.into(),
+        created: 0,
+        metadata: Some(Metadata {
+            pf_encryption: Some(PfEncryption {
+                alg: "AES-GCM".into(),
+                iv: IV.into(),
+            }),
+            ..Default::default()
+        }),
+    }
+}
+
+#[test]
+fn decrypt_webcrypto_paste() {
+    let key: EncryptionKey = KEY.parse().unwrap();
+    let expected: [u8; 32] = std::array::from_fn(|i| i as u8);
+    assert_eq!(key.as_bytes(), &expected);
+
+    assert!(paste().is_encrypted());
+    assert_eq!(paste().decrypt(&key).unwrap(), PLAINTEXT);
+}
+
+#[test]
+fn decrypt_with_wrong_key_fails() {
+    let key = EncryptionKey::from_bytes([0; 32]);
+    assert!(paste().decrypt(&key).is_err());
+}
+
+#[test]
+fn key_round_trips_through_url_fragment() {
+    let key: EncryptionKey = KEY.parse().unwrap();
+    assert_eq!(key.to_string(), format!("{KEY}="));
+    assert_eq!(key.to_string().parse::<EncryptionKey>().unwrap(), key);
+}
+
+// Produced like the vector above, with the key exported via
+// `crypto.subtle.exportKey("raw", key)` and encoded with `btoa`, as done
+// by the frontend for the fragment of share links.
+const SHARED_KEY: &str = "//z59vPw7ern5OHe29jV0s/MycbDwL26t7SxrquopaI=";
+const SHARED_IV: &str = "8PHy8/T19vf4+fr7";
+const SHARED_CONTENT: &str =
+    "EcEObHClyMqf1w1JyUUKgQBZOvw8A4yZdhDXbRGu9WcA60bi2GYnumf0+4jK6XJzUAlfQ8oLDBXxVyuanFM=";
+const SHARED_PLAINTEXT: &str = "encrypted in the browser, shared via link 🔑";
+
+#[test]
+fn share_link_fragment_matches_frontend() {
+    let expected: [u8; 32] = std::array::from_fn(|i| 255 - i as u8 * 3);
+    let key = EncryptionKey::from_bytes(expected);
+
+    let url =
+        ShareUrl::new("https://pasty.lus.pm".parse().unwrap(), "abcdef").with_key(key.to_string());
+    assert_eq!(url.to_url().fragment(), Some(SHARED_KEY));
+    assert_eq!(
+        url.to_string(),
+        format!("https://pasty.lus.pm/abcdef#{SHARED_KEY}")
+    );
+
+    let url: ShareUrl = url.to_string().parse().unwrap();
+    let key = url.encryption_key().unwrap().unwrap();
+    assert_eq!(key.as_bytes(), &expected);
+
+    let paste = Paste {
+        id: "abcdef".into(),
+        content: SHARED_CONTENT.into(),
+        created: 0,
+        metadata: Some(Metadata {
+            pf_encryption: Some(PfEncryption {
+                alg: "AES-GCM".into(),
+                iv: SHARED_IV.into(),
+            }),
+            ..Default::default()
+        }),
+    };
+    assert_eq!(paste.decrypt(&key).unwrap(), SHARED_PLAINTEXT);
+}
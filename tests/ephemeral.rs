@@ -0,0 +1,54 @@
+#![cfg(feature = "testing")]
+
+use pasty_rs::testing::FakeServer;
+use std::time::Duration;
+
+#[tokio::test]
+async fn close_deletes_paste() {
+    let server = FakeServer::start().await;
+    let paste = server
+        .client()
+        .create_ephemeral_paste("diagnostics", None)
+        .await
+        .unwrap();
+
+    let id = paste.id().to_string();
+    assert!(server.paste(&id).is_some());
+    paste.close().await.unwrap();
+    assert!(server.paste(&id).is_none());
+}
+
+#[tokio::test]
+async fn keep_disarms_guard() {
+    let server = FakeServer::start().await;
+    let paste = server
+        .client()
+        .create_ephemeral_paste("diagnostics", None)
+        .await
+        .unwrap();
+
+    let handle = paste.keep();
+    tokio::task::yield_now().await;
+    assert!(server.paste(handle.id()).is_some());
+}
+
+#[tokio::test]
+async fn drop_deletes_paste_while_runtime_is_running() {
+    let server = FakeServer::start().await;
+    let paste = server
+        .client()
+        .create_ephemeral_paste("diagnostics", None)
+        .await
+        .unwrap();
+
+    let id = paste.id().to_string();
+    drop(paste);
+
+    for _ in 0..100 {
+        if server.paste(&id).is_none() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("ephemeral paste {id} has not been deleted on drop");
+}
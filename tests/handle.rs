@@ -0,0 +1,28 @@
+#![cfg(feature = "testing")]
+
+use pasty_rs::{client::PasteHandle, testing::FakeServer};
+
+#[tokio::test]
+async fn restored_handle_reloads_and_deletes() {
+    let server = FakeServer::start().await;
+    let handle = server
+        .client()
+        .create_paste_handle("hello pasty!", None)
+        .await
+        .unwrap();
+    let id = handle.id().to_string();
+    let token = handle.token().expose().to_string();
+
+    let stored = serde_json::to_string(&handle).unwrap();
+    assert!(stored.contains(server.url().as_str()));
+    drop(handle);
+
+    let handle: PasteHandle = serde_json::from_str(&stored).unwrap();
+    let handle = handle.with_client(server.client());
+    assert_eq!(handle.id(), id);
+    assert_eq!(handle.token().expose(), token);
+
+    assert_eq!(handle.reload().await.unwrap().content, "hello pasty!");
+    handle.delete().await.unwrap();
+    assert!(server.paste(&id).is_none());
+}
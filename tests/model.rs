@@ -0,0 +1,156 @@
+use pasty_rs::model::{
+    AdminToken, ApplicationInformation, CachedPaste, CreatePasteRequest, CreatedPaste,
+    ErrorResponse, Metadata, ModificationToken, Paste, PasteUpdate, PfEncryption, ReportRequest,
+    ReportResponse, SecretToken,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt::Debug;
+
+fn round_trip<T>(value: &T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let json = serde_json::to_string(value).unwrap();
+    let decoded: T = serde_json::from_str(&json).unwrap();
+    assert_eq!(&decoded, value, "round trip via {json}");
+}
+
+fn metadata() -> Metadata {
+    let mut metadata = Metadata {
+        pf_encryption: Some(PfEncryption {
+            alg: "AES-GCM".into(),
+            iv: "aXY=".into(),
+        }),
+        ..Default::default()
+    };
+    metadata.set("build", &1234).unwrap();
+    metadata.set("tags", &["ci", "nightly"]).unwrap();
+    metadata
+}
+
+fn paste() -> Paste {
+    Paste {
+        id: "abcdef".into(),
+        content: "hello pasty!".into(),
+        created: 1_700_000_000,
+        metadata: Some(metadata()),
+    }
+}
+
+#[test]
+fn application_information() {
+    round_trip(&ApplicationInformation {
+        modification_tokens: true,
+        paste_lifetime: -1,
+        reports: false,
+        version: "v0.0.0".into(),
+    });
+}
+
+#[test]
+fn pf_encryption() {
+    round_trip(&PfEncryption {
+        alg: "AES-GCM".into(),
+        iv: "aXY=".into(),
+    });
+}
+
+#[test]
+fn metadata_round_trip() {
+    round_trip(&metadata());
+    round_trip(&Metadata::default());
+}
+
+#[test]
+fn paste_round_trip() {
+    round_trip(&paste());
+    round_trip(&Paste::<Metadata> {
+        metadata: None,
+        ..paste()
+    });
+}
+
+#[test]
+fn paste_with_custom_metadata() {
+    #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+    struct BuildInfo {
+        build_id: u64,
+    }
+
+    round_trip(&Paste {
+        id: "abcdef".into(),
+        content: "build log".into(),
+        created: 1_700_000_000,
+        metadata: Some(BuildInfo { build_id: 1234 }),
+    });
+}
+
+#[test]
+fn cached_paste() {
+    round_trip(&CachedPaste {
+        paste: paste(),
+        stale: true,
+        fetched: 1_700_000_100,
+    });
+}
+
+#[test]
+fn create_paste_request() {
+    round_trip(&CreatePasteRequest {
+        content: "hello pasty!".into(),
+        metadata: Some(metadata()),
+    });
+}
+
+#[test]
+fn paste_update() {
+    round_trip(&PasteUpdate::default());
+    round_trip(
+        &PasteUpdate::new()
+            .content("new content")
+            .set_metadata_key("build", &1234)
+            .unwrap()
+            .remove_metadata_key("draft"),
+    );
+}
+
+#[test]
+fn tokens() {
+    round_trip(&SecretToken::new("some-token"));
+    round_trip(&ModificationToken::new("some-token"));
+    round_trip(&AdminToken::new("some-admin-token"));
+    assert_eq!(
+        serde_json::to_string(&ModificationToken::new("some-token")).unwrap(),
+        r#""some-token""#
+    );
+}
+
+#[test]
+fn created_paste() {
+    round_trip(&CreatedPaste {
+        modification_token: ModificationToken::new("some-token"),
+        paste: paste(),
+    });
+}
+
+#[test]
+fn report_request() {
+    round_trip(&ReportRequest {
+        reason: "spam".into(),
+    });
+}
+
+#[test]
+fn report_response() {
+    round_trip(&ReportResponse {
+        success: true,
+        message: "reported".into(),
+    });
+}
+
+#[test]
+fn error_response() {
+    round_trip(&ErrorResponse {
+        message: "paste not found".into(),
+    });
+}
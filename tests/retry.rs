@@ -0,0 +1,76 @@
+#![cfg(feature = "testing")]
+
+use pasty_rs::{
+    client::RetryPolicy,
+    testing::{Failure, FakeServer},
+    transport::StatusCode,
+};
+use std::time::{Duration, Instant};
+
+fn policy() -> RetryPolicy {
+    RetryPolicy::new(3)
+        .initial_backoff(Duration::from_millis(10))
+        .max_backoff(Duration::from_secs(2))
+}
+
+#[tokio::test]
+async fn retries_server_errors() {
+    let server = FakeServer::start().await;
+    let client = server.client().with_retry_policy(policy());
+
+    server.fail_next(StatusCode::SERVICE_UNAVAILABLE);
+    server.fail_next(StatusCode::BAD_GATEWAY);
+    client.application_information().await.unwrap();
+    assert_eq!(server.request_count(), 3);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_retries() {
+    let server = FakeServer::start().await;
+    let client = server.client().with_retry_policy(policy());
+
+    for _ in 0..4 {
+        server.fail_next(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    let err = client.application_information().await.unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    assert_eq!(server.request_count(), 4);
+}
+
+#[tokio::test]
+async fn honors_retry_after() {
+    let server = FakeServer::start().await;
+    let client = server.client().with_retry_policy(policy());
+
+    server
+        .fail_next(Failure::new(StatusCode::TOO_MANY_REQUESTS).retry_after(Duration::from_secs(1)));
+    let started = Instant::now();
+    client.application_information().await.unwrap();
+    assert!(started.elapsed() >= Duration::from_secs(1));
+    assert_eq!(server.request_count(), 2);
+}
+
+#[tokio::test]
+async fn does_not_wait_for_retry_after_beyond_max_backoff() {
+    let server = FakeServer::start().await;
+    let client = server.client().with_retry_policy(policy());
+
+    server.fail_next(
+        Failure::new(StatusCode::TOO_MANY_REQUESTS).retry_after(Duration::from_secs(86400)),
+    );
+    let started = Instant::now();
+    let err = client.application_information().await.unwrap_err();
+    assert!(err.is_rate_limited());
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert_eq!(server.request_count(), 1);
+}
+
+#[tokio::test]
+async fn does_not_retry_create_by_default() {
+    let server = FakeServer::start().await;
+    let client = server.client().with_retry_policy(policy());
+
+    server.fail_next(StatusCode::SERVICE_UNAVAILABLE);
+    assert!(client.create_paste("hello", None).await.is_err());
+    assert_eq!(server.request_count(), 1);
+}
@@ -0,0 +1,125 @@
+use pasty_rs::token_store::{FileTokenStore, TokenStore};
+use std::{
+    env, fs,
+    path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+use url::Url;
+
+fn temp_path() -> PathBuf {
+    static DIRS: AtomicUsize = AtomicUsize::new(0);
+    env::temp_dir()
+        .join(format!(
+            "pasty-rs-token-store-{}-{}",
+            process::id(),
+            DIRS.fetch_add(1, Ordering::Relaxed)
+        ))
+        .join("tokens.json")
+}
+
+fn host() -> Url {
+    Url::parse("https://pasty.lus.pm/").unwrap()
+}
+
+#[test]
+fn set_get_remove() {
+    let store = FileTokenStore::new(temp_path());
+
+    assert_eq!(store.get(&host(), "abcdef").unwrap(), None);
+    store.set(&host(), "abcdef", "token").unwrap();
+    assert_eq!(
+        store.get(&host(), "abcdef").unwrap().as_deref(),
+        Some("token")
+    );
+
+    let other = Url::parse("https://other.example/").unwrap();
+    assert_eq!(store.get(&other, "abcdef").unwrap(), None);
+
+    store.remove(&host(), "abcdef").unwrap();
+    assert_eq!(store.get(&host(), "abcdef").unwrap(), None);
+
+    fs::remove_dir_all(store.path().parent().unwrap()).unwrap();
+}
+
+#[test]
+fn concurrent_stores_do_not_lose_tokens() {
+    let path = temp_path();
+
+    thread::scope(|s| {
+        for i in 0..8 {
+            let path = path.clone();
+            s.spawn(move || {
+                // Separate instances do not share any in-process state,
+                // like separate processes.
+                let store = FileTokenStore::new(path);
+                for j in 0..10 {
+                    store.set(&host(), &format!("{i}-{j}"), "token").unwrap();
+                }
+            });
+        }
+    });
+
+    let store = FileTokenStore::new(&path);
+    for i in 0..8 {
+        for j in 0..10 {
+            assert!(store.get(&host(), &format!("{i}-{j}")).unwrap().is_some());
+        }
+    }
+
+    let leftovers: Vec<_> = fs::read_dir(path.parent().unwrap())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .filter(|name| name.to_string_lossy().ends_with(".tmp"))
+        .collect();
+    assert!(leftovers.is_empty(), "left over temp files: {leftovers:?}");
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn token_file_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let store = FileTokenStore::new(temp_path());
+    store.set(&host(), "abcdef", "token").unwrap();
+
+    let mode = fs::metadata(store.path()).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    fs::remove_dir_all(store.path().parent().unwrap()).unwrap();
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn failing_store_does_not_fail_creation() {
+    use pasty_rs::{errors::Result, testing::FakeServer};
+
+    struct FailingStore;
+
+    impl TokenStore for FailingStore {
+        fn get(&self, _: &Url, _: &str) -> Result<Option<String>> {
+            Ok(None)
+        }
+
+        fn set(&self, _: &Url, _: &str, _: &str) -> Result<()> {
+            Err(std::io::Error::other("disk full").into())
+        }
+
+        fn remove(&self, _: &Url, _: &str) -> Result<()> {
+            Err(std::io::Error::other("disk full").into())
+        }
+    }
+
+    let server = FakeServer::start().await;
+    let client = server.client().with_token_store(FailingStore);
+
+    let created = client.create_paste("hello pasty!", None).await.unwrap();
+    assert!(server.paste(&created.paste.id).is_some());
+
+    let client = client.authenticate(created.modification_token);
+    client.delete_paste(&created.paste.id).await.unwrap();
+    assert!(server.paste(&created.paste.id).is_none());
+}
@@ -0,0 +1,148 @@
+#![cfg(all(feature = "testing", feature = "tracing"))]
+
+use pasty_rs::{testing::FakeServer, transport::StatusCode};
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Level, Metadata, Subscriber,
+};
+
+/// A subscriber recording all spans and events of this crate as lines of
+/// text.
+#[derive(Clone, Default)]
+struct Recorder {
+    lines: Arc<Mutex<Vec<String>>>,
+    ids: Arc<AtomicU64>,
+    spans: Arc<Mutex<HashMap<u64, &'static Metadata<'static>>>>,
+    stack: Arc<Mutex<Vec<span::Id>>>,
+}
+
+impl Recorder {
+    fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap().clone()
+    }
+
+    fn push(&self, line: String) {
+        self.lines.lock().unwrap().push(line);
+    }
+}
+
+struct Fields(String);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let _ = write!(self.0, " {}={value:?}", field.name());
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target().starts_with("pasty_rs")
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        let mut fields = Fields(format!("span {}", span.metadata().name()));
+        span.record(&mut fields);
+        self.push(fields.0);
+
+        let id = self.ids.fetch_add(1, Ordering::Relaxed) + 1;
+        self.spans.lock().unwrap().insert(id, span.metadata());
+        span::Id::from_u64(id)
+    }
+
+    fn record(&self, _: &span::Id, values: &span::Record<'_>) {
+        let mut fields = Fields("record".into());
+        values.record(&mut fields);
+        self.push(fields.0);
+    }
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields(format!("event {}", event.metadata().level()));
+        event.record(&mut fields);
+        self.push(fields.0);
+    }
+
+    fn enter(&self, span: &span::Id) {
+        self.stack.lock().unwrap().push(span.clone());
+    }
+
+    fn exit(&self, span: &span::Id) {
+        let mut stack = self.stack.lock().unwrap();
+        if let Some(pos) = stack.iter().rposition(|id| id == span) {
+            stack.remove(pos);
+        }
+    }
+
+    fn current_span(&self) -> tracing_core::span::Current {
+        match self.stack.lock().unwrap().last() {
+            Some(id) => tracing_core::span::Current::new(
+                id.clone(),
+                self.spans.lock().unwrap()[&id.into_u64()],
+            ),
+            None => tracing_core::span::Current::none(),
+        }
+    }
+}
+
+#[tokio::test]
+async fn spans_never_contain_tokens_or_contents() {
+    let server = FakeServer::start().await;
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+
+    let client = server.client();
+    let created = client
+        .create_paste("very secret content", None)
+        .await
+        .unwrap();
+    let token = created.modification_token.expose().to_string();
+    let id = created.paste.id.clone();
+
+    client.paste(&id).await.unwrap();
+    let client = client.authenticate(created.modification_token);
+    client
+        .update_paste(&id, "other secret content", None)
+        .await
+        .unwrap();
+    client.delete_paste(&id).await.unwrap();
+
+    let lines = recorder.lines();
+    assert!(lines.iter().any(|line| line.contains(&id)));
+    for line in &lines {
+        assert!(!line.contains(&token), "token recorded: {line}");
+        assert!(!line.contains("secret content"), "content recorded: {line}");
+    }
+}
+
+#[tokio::test]
+async fn failing_request_is_recorded_once() {
+    let server = FakeServer::start().await;
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+
+    server.fail_next(StatusCode::NOT_FOUND);
+    server
+        .client()
+        .paste_with_fallback("abcdef")
+        .await
+        .unwrap_err();
+
+    let lines = recorder.lines();
+    let errors: Vec<_> = lines
+        .iter()
+        .filter(|line| line.starts_with(&format!("event {}", Level::ERROR)))
+        .collect();
+    assert_eq!(errors.len(), 1, "{lines:#?}");
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("record") && line.contains("status=404")));
+}